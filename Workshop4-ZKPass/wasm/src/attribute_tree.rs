@@ -0,0 +1,268 @@
+use super::*;
+use crate::issuance::generate_salt;
+use crate::schema::struct_plaintext;
+use crate::values::parse_value;
+
+/// The opening of one attribute leaf, kept by the holder:
+///
+/// ```leo
+/// struct AttributeLeaf { attribute: field, value: <type>, salt: scalar }
+/// ```
+///
+/// The leaf is `Poseidon2::hash_to_field` of that struct, where `attribute` is [`hash_string`]
+/// of the attribute name. The name binds the leaf to one attribute, and the salt keeps a
+/// low-entropy value such as a date of birth from being guessed from the sibling hashes.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct AttributeLeaf<N: NetworkNative> {
+    pub name: String,
+    pub value: Plaintext<N>,
+    pub salt: Scalar<N>,
+}
+
+impl<N: NetworkNative> AttributeLeaf<N> {
+    /// Returns the `AttributeLeaf` struct the leaf is hashed from.
+    pub fn to_struct(&self) -> Result<Plaintext<N>, CustomError> {
+        let mut members = IndexMap::with_capacity(3);
+        members.insert("attribute".to_string(), Plaintext::from(Literal::Field(hash_string::<N>(&self.name)?)));
+        members.insert("value".to_string(), self.value.clone());
+        members.insert("salt".to_string(), Plaintext::from(Literal::Scalar(self.salt)));
+        struct_plaintext(members)
+    }
+
+    /// Returns the leaf hash.
+    pub fn hash(&self) -> Result<Field<N>, CustomError> {
+        let fields = Value::Plaintext(self.to_struct()?).to_fields()
+            .map_err(|e| CustomError::HashFailure(format!("Failed value to Fields conversion: {}", e)))?;
+        N::hash_psd2(&fields)
+            .map_err(|e| CustomError::HashFailure(format!("Failed hash_psd2 conversion: {}", e)))
+    }
+}
+
+/// A merkle credential whose leaves are bound to attribute names, see [`AttributeLeaf`].
+#[derive(Debug, Clone)]
+pub struct AttributeTree<N: NetworkNative> {
+    tree: CredentialTree<N>,
+    config: MerkleConfig,
+    attributes: Vec<AttributeLeaf<N>>,
+}
+
+impl<N: NetworkNative> AttributeTree<N> {
+    /// Builds a tree over the members of a JSON object such as `{ "dob": "20000101u32" }`, in
+    /// order, drawing a fresh salt for every leaf.
+    pub fn new(attributes: &JsonValue, mode: ParseMode, config: &MerkleConfig) -> Result<Self, CustomError> {
+        Self::new_with_rng(attributes, mode, config, &mut secure_rng())
+    }
+
+    /// Builds a tree like [`AttributeTree::new`], sampling the salts from `rng`.
+    pub fn new_with_rng<R: SigningRng>(attributes: &JsonValue, mode: ParseMode, config: &MerkleConfig, rng: &mut R) -> Result<Self, CustomError> {
+        let attributes = attributes.as_object()
+            .ok_or_else(|| CustomError::parse("attributes", "Attributes must be a JSON object"))?;
+        let mut leaves = Vec::with_capacity(attributes.len());
+        for (name, value) in attributes {
+            leaves.push(AttributeLeaf {
+                name: name.clone(),
+                value: parse_value::<N>(name, value, mode)?,
+                salt: generate_salt::<N, R>(rng),
+            });
+        }
+        Self::from_leaves(leaves, config)
+    }
+
+    /// Rebuilds a tree from the leaves returned to the holder.
+    pub fn from_leaves(attributes: Vec<AttributeLeaf<N>>, config: &MerkleConfig) -> Result<Self, CustomError> {
+        for (i, attribute) in attributes.iter().enumerate() {
+            if attribute.name.is_empty() {
+                return Err(CustomError::parse(format!("attributes[{}].name", i), "Attribute names must not be empty"));
+            }
+            if attributes[..i].iter().any(|other| other.name == attribute.name) {
+                return Err(CustomError::parse(&attribute.name, "Duplicate attribute"));
+            }
+        }
+        let leaves = attributes.iter().map(AttributeLeaf::hash).collect::<Result<Vec<_>, _>>()?;
+        Ok(Self { tree: CredentialTree::new(leaves, config)?, config: *config, attributes })
+    }
+
+    /// Rebuilds the tree of a credential returned by [`AttributeTree::credential`], with the
+    /// configuration it was built with, and checks that it has the committed root.
+    pub fn from_credential(credential: AttributeCredential<N>) -> Result<Self, CustomError> {
+        let tree = Self::from_leaves(credential.attributes, &credential.config)?;
+        if tree.tree.merkle_root() != credential.root {
+            return Err(CustomError::VerificationFailed("The attributes do not rebuild the credential root".to_string()));
+        }
+        Ok(tree)
+    }
+
+    pub fn tree(&self) -> &CredentialTree<N> {
+        &self.tree
+    }
+
+    pub fn config(&self) -> &MerkleConfig {
+        &self.config
+    }
+
+    /// The attribute leaves with their salts, to be handed to the holder.
+    pub fn attributes(&self) -> &[AttributeLeaf<N>] {
+        &self.attributes
+    }
+
+    /// Returns the committed root together with the configuration and the leaves to hand to the
+    /// holder, everything [`AttributeTree::from_credential`] needs to rebuild the tree.
+    pub fn credential(&self) -> AttributeCredential<N> {
+        AttributeCredential { root: self.tree.merkle_root(), config: self.config, attributes: self.attributes.clone() }
+    }
+
+    /// Returns the proof of the attribute called `name`.
+    pub fn get_proof(&self, name: &str) -> Result<AttributeProof<N>, CustomError> {
+        let index = self.attributes.iter().position(|attribute| attribute.name == name)
+            .ok_or_else(|| CustomError::MissingMember(name.to_string()))?;
+        Ok(AttributeProof { attribute: self.attributes[index].clone(), proof: self.tree.get_proof(index)? })
+    }
+
+    /// Returns one proof of all the attributes called `names`, see [`MerkleMultiproof`].
+    pub fn get_multiproof(&self, names: &[String]) -> Result<AttributeMultiproof<N>, CustomError> {
+        let indices = names.iter().map(|name| self.attributes.iter().position(|attribute| &attribute.name == name)
+            .ok_or_else(|| CustomError::MissingMember(name.clone()))).collect::<Result<Vec<_>, _>>()?;
+        let proof = self.tree.get_multiproof(&indices)?;
+        let attributes = proof.indices.iter().map(|&index| self.attributes[index].clone()).collect();
+        Ok(AttributeMultiproof { attributes, proof })
+    }
+}
+
+/// What the issuer returns: the root to sign, the configuration of the tree, and the salted
+/// leaves the holder keeps.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct AttributeCredential<N: NetworkNative> {
+    pub root: MerkleRoot<N>,
+    pub config: MerkleConfig,
+    pub attributes: Vec<AttributeLeaf<N>>,
+}
+
+/// Discloses one attribute of an [`AttributeTree`]: its opening and its merkle proof.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct AttributeProof<N: NetworkNative> {
+    pub attribute: AttributeLeaf<N>,
+    pub proof: MerkleProof<N>,
+}
+
+impl<N: NetworkNative> AttributeProof<N> {
+    /// Returns whether the disclosed attribute is in the tree with the committed `root`.
+    pub fn verify(&self, root: &Field<N>) -> Result<bool, CustomError> {
+        self.proof.verify(root, &self.attribute.hash()?)
+    }
+}
+
+/// Discloses several attributes of an [`AttributeTree`] at once, in the order of the proven
+/// indices.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct AttributeMultiproof<N: NetworkNative> {
+    pub attributes: Vec<AttributeLeaf<N>>,
+    pub proof: MerkleMultiproof<N>,
+}
+
+impl<N: NetworkNative> AttributeMultiproof<N> {
+    /// Returns whether all the disclosed attributes are in the tree with the committed `root`.
+    pub fn verify(&self, root: &Field<N>) -> Result<bool, CustomError> {
+        let leaves = self.attributes.iter().map(AttributeLeaf::hash).collect::<Result<Vec<_>, _>>()?;
+        self.proof.verify(root, &leaves)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rng::deterministic_rng;
+    use serde_json::json;
+
+    // Define the network type for the tests
+    type N = TestnetV0;
+
+    fn attributes() -> JsonValue {
+        json!({ "dob": "20000101u32", "nationality": "123field", "expiry": { "type": "u32", "value": 20300101 } })
+    }
+
+    #[test]
+    fn test_attribute_proofs_by_name() {
+        let config = MerkleConfig::default().with_hasher(MerkleHashAlgorithm::Poseidon2);
+        let tree = AttributeTree::<N>::new_with_rng(&attributes(), ParseMode::Strict, &config, &mut deterministic_rng(3)).unwrap();
+        let root = tree.tree().root();
+
+        let proof = tree.get_proof("nationality").unwrap();
+        assert_eq!(proof.attribute.value.to_string(), "123field");
+        assert_eq!(proof.proof.index, 1);
+        assert!(proof.verify(&root).unwrap());
+
+        // The leaf is bound to the attribute name, the value and the salt
+        for tamper in [
+            |proof: &mut AttributeProof<N>| proof.attribute.name = "dob".to_string(),
+            |proof: &mut AttributeProof<N>| proof.attribute.value = Plaintext::from_str("124field").unwrap(),
+            |proof: &mut AttributeProof<N>| proof.attribute.salt = Scalar::zero(),
+        ] {
+            let mut tampered = proof.clone();
+            tamper(&mut tampered);
+            assert!(!tampered.verify(&root).unwrap());
+        }
+        assert!(matches!(tree.get_proof("name"), Err(CustomError::MissingMember(_))));
+
+        // Several attributes are disclosed with one proof, in tree order
+        let names = ["expiry".to_string(), "dob".to_string()];
+        let multiproof = tree.get_multiproof(&names).unwrap();
+        assert_eq!(multiproof.attributes.iter().map(|attribute| attribute.name.as_str()).collect::<Vec<_>>(), ["dob", "expiry"]);
+        assert!(multiproof.verify(&root).unwrap());
+        let mut tampered = multiproof.clone();
+        tampered.attributes[1].value = Plaintext::from_str("20400101u32").unwrap();
+        assert!(!tampered.verify(&root).unwrap());
+        assert!(matches!(tree.get_multiproof(&["dob".to_string(), "name".to_string()]), Err(CustomError::MissingMember(_))));
+
+        // The holder rebuilds the same tree from the returned credential alone
+        let json = serde_json::to_string(&tree.credential()).unwrap();
+        let credential: AttributeCredential<N> = serde_json::from_str(&json).unwrap();
+        assert_eq!(credential.root.root, root);
+        assert_eq!(credential.config, config);
+        assert_eq!(AttributeTree::from_credential(credential).unwrap().tree().root(), root);
+    }
+
+    #[test]
+    fn test_credential_keeps_the_config() {
+        // A padded, fixed-depth tree cannot be rebuilt from the leaves and the hasher alone
+        let config = MerkleConfig::with_depth(3, MerklePadding::Sentinel).unwrap().with_hasher(MerkleHashAlgorithm::Bhp256);
+        let tree = AttributeTree::<N>::new_with_rng(&attributes(), ParseMode::Strict, &config, &mut deterministic_rng(4)).unwrap();
+        let credential = tree.credential();
+        let guessed = MerkleConfig::default().with_hasher(credential.root.hasher);
+        assert_ne!(AttributeTree::from_leaves(credential.attributes.clone(), &guessed).unwrap().tree().root(), tree.tree().root());
+
+        let rebuilt = AttributeTree::from_credential(credential.clone()).unwrap();
+        assert_eq!(rebuilt.config(), &config);
+        assert_eq!(rebuilt.tree().merkle_root(), credential.root);
+        assert!(rebuilt.get_proof("expiry").unwrap().verify(&credential.root.root).unwrap());
+
+        // The stored config must rebuild the committed root
+        let mut mismatched = credential;
+        mismatched.config = MerkleConfig::with_depth(3, MerklePadding::Zero).unwrap().with_hasher(MerkleHashAlgorithm::Bhp256);
+        assert!(matches!(AttributeTree::from_credential(mismatched), Err(CustomError::VerificationFailed(_))));
+    }
+
+    #[test]
+    fn test_attribute_leaves_are_salted() {
+        let config = MerkleConfig::default();
+        let first = AttributeTree::<N>::new_with_rng(&attributes(), ParseMode::Strict, &config, &mut deterministic_rng(1)).unwrap();
+        let second = AttributeTree::<N>::new_with_rng(&attributes(), ParseMode::Strict, &config, &mut deterministic_rng(2)).unwrap();
+        assert_ne!(first.tree().leaves()[0], second.tree().leaves()[0]);
+        assert_ne!(first.attributes()[0].salt, second.attributes()[0].salt);
+
+        // The same value under another name is another leaf
+        let mut renamed = first.attributes()[0].clone();
+        renamed.name = "birthday".to_string();
+        assert_ne!(renamed.hash().unwrap(), first.tree().leaves()[0]);
+        assert_eq!(first.attributes()[0].to_struct().unwrap().to_string().matches("20000101u32").count(), 1);
+
+        let mut duplicate = first.attributes().to_vec();
+        duplicate[1].name = "dob".to_string();
+        assert_eq!(AttributeTree::from_leaves(duplicate, &config).unwrap_err().path(), Some("dob"));
+        assert!(AttributeTree::<N>::new(&json!(["20000101u32"]), ParseMode::Strict, &config).is_err());
+        assert_eq!(AttributeTree::<N>::new(&json!({ "dob": "tomorrow" }), ParseMode::Strict, &config).unwrap_err().path(), Some("dob"));
+    }
+}
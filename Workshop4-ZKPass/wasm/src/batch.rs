@@ -0,0 +1,160 @@
+use super::*;
+use crate::verification::verify_credential_with_issuer;
+
+/// A credential to verify as part of a batch, written in JSON as
+/// `{ data, schema?, mode?, signature, hash? }`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchCredential {
+    #[serde(flatten)]
+    pub message: SignInboundMessage,
+    pub signature: String,
+    /// The hash returned when the credential was signed, used to detect tampered data.
+    #[serde(default)]
+    pub hash: Option<String>,
+}
+
+/// Per-item outcome of a batch, serialized as `{ "outcome": "ok", ...item }` or
+/// `{ "outcome": "error", code, message, path }`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "outcome", rename_all = "snake_case")]
+pub enum BatchResult<T> {
+    Ok(T),
+    Error(BatchError),
+}
+
+/// A [`CustomError`] flattened into its code, message and path.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BatchError {
+    pub code: String,
+    pub message: String,
+    pub path: Option<String>,
+}
+
+impl From<&CustomError> for BatchError {
+    fn from(error: &CustomError) -> Self {
+        Self {
+            code: error.code().to_string(),
+            message: error.to_string(),
+            path: error.path().map(str::to_string),
+        }
+    }
+}
+
+impl<T> From<Result<T, CustomError>> for BatchResult<T> {
+    fn from(result: Result<T, CustomError>) -> Self {
+        match result {
+            Ok(value) => Self::Ok(value),
+            Err(error) => Self::Error(BatchError::from(&error)),
+        }
+    }
+}
+
+/// Per-message `(signature, hash)` results of a signing batch.
+pub type SignedBatch = Vec<Result<(String, String), CustomError>>;
+
+/// Signs every message with one private key and returns `(signature, hash)` per message.
+///
+/// The key is parsed once and signatures are not verified again after signing. A message that
+/// fails to encode only fails its own entry; an invalid private key fails the whole batch.
+/// With the `parallel` feature the messages are signed across all cores on native targets.
+pub fn sign_messages_batch<N: NetworkNative>(private_key: &str, messages: &[SignInboundMessage], hash: HashAlgorithm) -> Result<SignedBatch, CustomError> {
+    let private_key = parse_private_key::<N>(private_key)?;
+    Ok(map_batch(messages, |message| sign_with_key::<N, _>(&private_key, message, hash, &mut secure_rng())))
+}
+
+/// Signs a batch like [`sign_messages_batch`], sequentially and sampling every nonce from `rng`.
+pub fn sign_messages_batch_with_rng<N: NetworkNative, R: SigningRng>(private_key: &str, messages: &[SignInboundMessage], hash: HashAlgorithm, rng: &mut R) -> Result<SignedBatch, CustomError> {
+    let private_key = parse_private_key::<N>(private_key)?;
+    Ok(messages.iter().map(|message| sign_with_key::<N, R>(&private_key, message, hash, rng)).collect())
+}
+
+/// Verifies every credential against one issuer, see [`verification::verify_credential`].
+///
+/// The issuer address is parsed once. Malformed credentials only fail their own entry.
+pub fn verify_credentials_batch<N: NetworkNative>(credentials: &[BatchCredential], hash: HashAlgorithm, issuer: &str) -> Result<Vec<Result<CredentialVerification, CustomError>>, CustomError> {
+    let issuer = Address::<N>::from_str(issuer)
+        .map_err(|e| CustomError::parse("issuer", e))?;
+    Ok(map_batch(credentials, |credential| {
+        verify_credential_with_issuer::<N>(&credential.message, hash, &credential.signature, &issuer, credential.hash.as_deref())
+    }))
+}
+
+fn sign_with_key<N: NetworkNative, R: SigningRng>(private_key: &PrivateKey<N>, message: &SignInboundMessage, hash: HashAlgorithm, rng: &mut R) -> Result<(String, String), CustomError> {
+    let hash = credential_hash(&credential_message::<N>(message)?, message, hash)?;
+    let hash_fields = string_to_value_fields::<N>(&hash)?;
+    let signature = sign_message_with_private_key(private_key, &hash_fields, rng)?;
+    Ok((signature.to_string(), hash))
+}
+
+#[cfg(all(feature = "parallel", not(target_arch = "wasm32")))]
+fn map_batch<T: Sync, U: Send>(items: &[T], f: impl Fn(&T) -> U + Sync + Send) -> Vec<U> {
+    use rayon::prelude::*;
+    items.par_iter().map(f).collect()
+}
+
+#[cfg(not(all(feature = "parallel", not(target_arch = "wasm32"))))]
+fn map_batch<T, U>(items: &[T], f: impl Fn(&T) -> U) -> Vec<U> {
+    items.iter().map(f).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rng::deterministic_rng;
+    use serde_json::json;
+
+    // Define the network type for the tests
+    type N = TestnetV0;
+
+    const PRIVATE_KEY: &str = "APrivateKey1zkp5LqRmm7535XfiX77VPQEgsS2Dj1B2DvH4QNP1UYrHEoR";
+    const ISSUER: &str = "aleo1ekyuzclmcw3aj7qncsxxaapxem82mgrd8zadgrrvl5k705zx6q9s7usuqy";
+
+    fn messages() -> Vec<SignInboundMessage> {
+        ["20000101u32", "tomorrow", "19991231u32"].into_iter()
+            .map(|dob| SignInboundMessage::from_json(json!({ "issuer": ISSUER, "dob": dob })).with_mode(ParseMode::Strict))
+            .collect()
+    }
+
+    #[test]
+    fn test_sign_messages_batch_reports_per_item_errors() {
+        let results = sign_messages_batch::<N>(PRIVATE_KEY, &messages(), HashAlgorithm::POSEIDON2).unwrap();
+        assert_eq!(results.len(), 3);
+        assert!(results[0].is_ok() && results[2].is_ok());
+        assert_eq!(results[1].as_ref().unwrap_err().path(), Some("dob"));
+
+        assert!(matches!(sign_messages_batch::<N>("APrivateKey1invalid", &messages(), HashAlgorithm::POSEIDON2), Err(CustomError::InvalidKey(_))));
+    }
+
+    #[test]
+    fn test_sign_messages_batch_matches_single_signing() {
+        let batch = sign_messages_batch_with_rng::<N, _>(PRIVATE_KEY, &messages(), HashAlgorithm::POSEIDON2, &mut deterministic_rng(7)).unwrap();
+
+        let mut rng = deterministic_rng(7);
+        let first = sign_message_with_rng::<N, _>(PRIVATE_KEY.to_string(), messages().remove(0), HashAlgorithm::POSEIDON2, &StdoutLogger, &mut rng).unwrap();
+        let third = sign_message_with_rng::<N, _>(PRIVATE_KEY.to_string(), messages().remove(2), HashAlgorithm::POSEIDON2, &StdoutLogger, &mut rng).unwrap();
+        assert_eq!(batch[0].as_ref().unwrap(), &first);
+        assert_eq!(batch[2].as_ref().unwrap(), &third);
+    }
+
+    #[test]
+    fn test_verify_credentials_batch() {
+        let signed = sign_messages_batch::<N>(PRIVATE_KEY, &messages(), HashAlgorithm::POSEIDON2).unwrap();
+        let (signature, hash) = signed[0].as_ref().unwrap().clone();
+
+        let credentials: Vec<BatchCredential> = serde_json::from_value(json!([
+            { "data": { "issuer": ISSUER, "dob": "20000101u32" }, "mode": "strict", "signature": signature, "hash": hash },
+            { "data": { "issuer": ISSUER, "dob": "19000101u32" }, "signature": signature },
+            { "data": { "issuer": ISSUER, "dob": "20000101u32" }, "signature": "sign1invalid" },
+        ])).unwrap();
+        let results = verify_credentials_batch::<N>(&credentials, HashAlgorithm::POSEIDON2, ISSUER).unwrap();
+        assert!(results[0].as_ref().unwrap().is_valid());
+        assert_eq!(results[1].as_ref().unwrap().status(), VerificationStatus::BadSignature);
+        assert_eq!(results[2].as_ref().unwrap_err().path(), Some("signature"));
+
+        let serialized = serde_json::to_value(results.into_iter().map(BatchResult::from).collect::<Vec<_>>()).unwrap();
+        assert_eq!(serialized[0]["outcome"], "ok");
+        assert_eq!(serialized[0]["status"], "valid");
+        assert_eq!(serialized[2]["outcome"], "error");
+        assert_eq!(serialized[2]["code"], "PARSE_ERROR");
+    }
+}
@@ -0,0 +1,163 @@
+use super::*;
+use crate::hiding::{hide_members, salt_member};
+use crate::schema::struct_plaintext;
+
+/// The struct a ZPass `invalidated` mapping key is committed from.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InvalidationLayout {
+    /// `zpass_invalidate.aleo`: `FullCredentials { issuer, subject, dob, nationality, expiry, salt }`.
+    FullCredentials = 0,
+    /// `zpass_invalidate_hiding.aleo`: `InvalidateZPass`, where every member but `subject` and
+    /// `salt` is the group commitment stored in the hiding `ZPass` record.
+    InvalidateZPass = 1,
+}
+
+impl InvalidationLayout {
+    /// Returns the schema of the committed struct, read from the program source.
+    pub fn schema(&self) -> Result<CredentialSchema, CustomError> {
+        match self {
+            Self::FullCredentials => ZPassProgram::ZPassInvalidate.schema("FullCredentials"),
+            Self::InvalidateZPass => ZPassProgram::ZPassInvalidateHiding.schema("InvalidateZPass"),
+        }
+    }
+}
+
+/// Computes `BHP256::commit_to_group(value, salt)`.
+pub fn commit_to_group<N: NetworkNative>(value: &Plaintext<N>, salt: &Scalar<N>) -> Result<Group<N>, CustomError> {
+    N::commit_to_group_bhp256(&value.to_bits_le(), salt)
+        .map_err(|e| CustomError::HashFailure(format!("Failed commit_to_group_bhp256 conversion: {}", e)))
+}
+
+/// Computes the `issued` mapping key of every ZPass program,
+/// `BHP256::commit_to_group(self.caller, pub.salt)` with the subject as caller.
+pub fn issuance_commitment<N: NetworkNative>(subject: &Address<N>, salt: &Scalar<N>) -> Result<Group<N>, CustomError> {
+    commit_to_group(&Plaintext::from(Literal::Address(*subject)), salt)
+}
+
+/// Fails with `ALREADY_ISSUED` if `key` is in a snapshot of the `issued` mapping keys, in which
+/// case the `issue` transition would be rejected by `issue_finalize`.
+pub fn ensure_not_issued<N: NetworkNative>(key: &Group<N>, issued: &[String]) -> Result<(), CustomError> {
+    for (i, entry) in issued.iter().enumerate() {
+        let entry = Group::<N>::from_str(entry.trim())
+            .map_err(|e| CustomError::parse(format!("issued[{}]", i), e))?;
+        if &entry == key {
+            return Err(CustomError::AlreadyIssued(key.to_string()));
+        }
+    }
+    Ok(())
+}
+
+/// Encodes `data` against `schema` and commits to the struct with its own `salt` member,
+/// `BHP256::commit_to_group(credentials, credentials.salt)`.
+pub fn commit_credentials<N: NetworkNative>(schema: &CredentialSchema, data: &JsonValue) -> Result<Group<N>, CustomError> {
+    let members = schema.encode::<N>(data)?;
+    let salt = salt_member(&members)?;
+    commit_to_group(&struct_plaintext(members)?, &salt)
+}
+
+/// Computes the `invalidated` mapping key of a credential for `layout`.
+///
+/// `credentials` holds the plain `FullCredentials` members, including the `salt`, exactly as
+/// signed by the issuer with the holder as `subject`. For [`InvalidationLayout::InvalidateZPass`]
+/// the hidden members are first committed with the salt, as `zpass_invalidate_hiding.aleo/issue`
+/// does when it creates the record. A holder who only has the hiding record can pass its
+/// members to [`commit_credentials`] with the `InvalidateZPass` schema instead.
+pub fn invalidation_commitment<N: NetworkNative>(credentials: &JsonValue, layout: InvalidationLayout) -> Result<Group<N>, CustomError> {
+    let full = InvalidationLayout::FullCredentials.schema()?;
+    match layout {
+        InvalidationLayout::FullCredentials => commit_credentials::<N>(&full, credentials),
+        InvalidationLayout::InvalidateZPass => {
+            let plain = full.encode::<N>(credentials)?;
+            let salt = salt_member(&plain)?;
+            let members = hide_members(&plain, &InvalidationLayout::InvalidateZPass.schema()?, &salt)?;
+            commit_to_group(&struct_plaintext(members)?, &salt)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    // Define the network type for the tests
+    type N = TestnetV0;
+
+    const ISSUER: &str = "aleo1ekyuzclmcw3aj7qncsxxaapxem82mgrd8zadgrrvl5k705zx6q9s7usuqy";
+    // The address of the `PRIVATE_KEY` in the programs' `.env.example`, so that `issue` can be
+    // run with the subject as caller
+    const SUBJECT: &str = "aleo1rhgdu77hgyqd3xjj8ucu3jj9r2krwz6mnzyd80gncr5fxcwlh5rsvzp9px";
+
+    // Written by Leo 2.4.1 with the subject's key: `leo run issue <sig> "{ issuer: ISSUER,
+    // subject: SUBJECT, dob: 20000101u32, nationality: 123field, expiry: 20300101u32 }"
+    // "{ salt: 42scalar }"`, where `sig` is the issuer's signature of `credentials()`, then
+    // `leo run invalidate` on the returned `ZPass` record. These are the `invalidated` keys
+    // passed to `invalidate_finalize` by zpass_invalidate.aleo and zpass_invalidate_hiding.aleo.
+    const FULL_CREDENTIALS_COMMITMENT: &str = "3032939608197004125638941655204210477067713904714292325313560410641985339491group";
+    const INVALIDATE_ZPASS_COMMITMENT: &str = "2534929029626870639879331377159276253494928110087476642157893690884670225446group";
+    // The `issued` key passed to `issue_finalize` by the same `issue` runs of both programs
+    const ISSUED_KEY: &str = "5737911007015864934668012534788428746146577459244541778467441993103410072604group";
+
+    fn credentials() -> JsonValue {
+        json!({
+            "issuer": ISSUER,
+            "subject": SUBJECT,
+            "dob": "20000101u32",
+            "nationality": "123field",
+            "expiry": "20300101u32",
+            "salt": "42scalar"
+        })
+    }
+
+    #[test]
+    fn test_full_credentials_invalidation_commitment() {
+        let commitment = invalidation_commitment::<N>(&credentials(), InvalidationLayout::FullCredentials).unwrap();
+        assert_eq!(commitment.to_string(), FULL_CREDENTIALS_COMMITMENT);
+
+        // Every member is part of the commitment, the subject included
+        let mut credentials = credentials();
+        credentials["subject"] = json!(ISSUER);
+        assert_ne!(invalidation_commitment::<N>(&credentials, InvalidationLayout::FullCredentials).unwrap(), commitment);
+    }
+
+    #[test]
+    fn test_invalidate_zpass_invalidation_commitment() {
+        let commitment = invalidation_commitment::<N>(&credentials(), InvalidationLayout::InvalidateZPass).unwrap();
+        assert_eq!(commitment.to_string(), INVALIDATE_ZPASS_COMMITMENT);
+
+        // The same key is computed from the members of the `ZPass` record returned by
+        // `zpass_invalidate_hiding.aleo/issue` in the run above
+        let record = json!({
+            "issuer": "6862918275823658547329480035805196283610782352407563523468697380710470479602group",
+            "subject": SUBJECT,
+            "dob": "6819386498006118184179470233967879409916195603061614022131978563964917114590group",
+            "nationality": "5080714264323746025746719955142645706596836666325017651421539017830023573333group",
+            "expiry": "5186963321594873217763742575006708034577252562014818285215684903328130316162group",
+            "salt": "42scalar"
+        });
+        assert_eq!(commit_credentials::<N>(&InvalidationLayout::InvalidateZPass.schema().unwrap(), &record).unwrap(), commitment);
+    }
+
+    #[test]
+    fn test_issuance_commitment() {
+        let subject = Address::<N>::from_str(SUBJECT).unwrap();
+        let salt = Scalar::<N>::from_str("42scalar").unwrap();
+        let key = issuance_commitment::<N>(&subject, &salt).unwrap();
+        assert_eq!(key.to_string(), ISSUED_KEY);
+
+        assert!(ensure_not_issued::<N>(&key, &["0group".to_string()]).is_ok());
+        assert_eq!(ensure_not_issued::<N>(&key, &["0group".to_string(), ISSUED_KEY.to_string()]).unwrap_err().code(), "ALREADY_ISSUED");
+        assert_eq!(ensure_not_issued::<N>(&key, &["42".to_string()]).unwrap_err().path(), Some("issued[0]"));
+    }
+
+    #[test]
+    fn test_invalidation_commitment_requires_scalar_salt() {
+        let mut credentials = credentials();
+        credentials.as_object_mut().unwrap().remove("salt");
+        assert!(matches!(invalidation_commitment::<N>(&credentials, InvalidationLayout::FullCredentials), Err(CustomError::MissingMember(_))));
+        credentials["salt"] = json!("42field");
+        assert!(invalidation_commitment::<N>(&credentials, InvalidationLayout::InvalidateZPass).is_err());
+    }
+}
@@ -0,0 +1,578 @@
+use super::*;
+use crate::helpers::{field_array, hash_to_field_psd2};
+use crate::merkle_tree::{hash_values, MerkleHasher};
+
+/// The deepest tree a merkle credential may use, 65536 leaves.
+pub const MAX_MERKLE_DEPTH: u32 = 16;
+
+/// How the leaves of a merkle credential are padded up to a power of two.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MerklePadding {
+    /// Pads with `0field`, like the fixed eight-leaf tree.
+    Zero = 0,
+    /// Pads the leaf at `index` with `Poseidon2::hash_to_field([tag, index])`, where `tag` is
+    /// [`hash_string`] of `zpass.merkle.padding`, so padding never equals a zero value leaf.
+    Sentinel = 1,
+    /// Does not pad: the leaves must fill the tree exactly.
+    Exact = 2,
+}
+
+/// The shape and node hash of a merkle credential tree.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MerkleConfig {
+    pub(crate) depth: Option<u32>,
+    pub(crate) padding: MerklePadding,
+    pub(crate) hasher: MerkleHashAlgorithm,
+}
+
+impl Default for MerkleConfig {
+    fn default() -> Self {
+        Self::new(MerklePadding::Zero)
+    }
+}
+
+impl MerkleConfig {
+    /// A tree as deep as needed for the leaves, padded with `padding` and hashed with
+    /// [`MerkleHashAlgorithm::Poseidon2`].
+    pub fn new(padding: MerklePadding) -> Self {
+        Self { depth: None, padding, hasher: MerkleHashAlgorithm::Poseidon2 }
+    }
+
+    /// A tree of exactly `depth` levels, holding up to `2^depth` leaves.
+    pub fn with_depth(depth: u32, padding: MerklePadding) -> Result<Self, CustomError> {
+        check_depth(depth as usize, "depth")?;
+        Ok(Self { depth: Some(depth), ..Self::new(padding) })
+    }
+
+    /// Hashes the nodes with `hasher` instead. Credential trees reject
+    /// [`MerkleHashAlgorithm::Sum`], which does not bind leaves to their positions.
+    pub fn with_hasher(mut self, hasher: MerkleHashAlgorithm) -> Self {
+        self.hasher = hasher;
+        self
+    }
+
+    pub fn depth(&self) -> Option<u32> {
+        self.depth
+    }
+
+    pub fn padding(&self) -> MerklePadding {
+        self.padding
+    }
+
+    pub fn hasher(&self) -> MerkleHashAlgorithm {
+        self.hasher
+    }
+
+    /// Returns the depth of a tree holding `leaf_count` leaves.
+    pub fn depth_for(&self, leaf_count: usize) -> Result<u32, CustomError> {
+        if leaf_count == 0 {
+            return Err(CustomError::parse("leaves", "Expected at least one leaf"));
+        }
+        let depth = match self.depth {
+            Some(depth) => depth,
+            None => leaf_count.next_power_of_two().trailing_zeros(),
+        };
+        let capacity = 1usize << depth.min(MAX_MERKLE_DEPTH);
+        if depth > MAX_MERKLE_DEPTH || leaf_count > capacity {
+            return Err(CustomError::IndexOutOfRange { index: leaf_count - 1, len: capacity });
+        }
+        if self.padding == MerklePadding::Exact && leaf_count != capacity {
+            return Err(CustomError::parse("leaves", format!("Expected exactly {} leaves without padding, found {}", capacity, leaf_count)));
+        }
+        Ok(depth)
+    }
+}
+
+/// A merkle tree over any number of credential leaves whose root commits to the leaf count, the
+/// depth and the node hash, see [`commit_root`].
+///
+/// Committing the count makes padding unambiguous: a proof names the leaf count, and no proof
+/// can be made for a padded position. Committing the depth fixes the length of every proof, and
+/// committing the hasher keeps a proof from being replayed under another node hash. Each leaf
+/// enters the tree as [`leaf_node`], so no internal node can be passed off as a leaf.
+#[derive(Debug, Clone)]
+pub struct CredentialTree<N: NetworkNative> {
+    tree: MerkleTree<N>,
+    leaves: Vec<Field<N>>,
+    root: Field<N>,
+}
+
+impl<N: NetworkNative> CredentialTree<N> {
+    /// Builds a tree over `leaves`, padded as `config` says.
+    pub fn new(leaves: Vec<Field<N>>, config: &MerkleConfig) -> Result<Self, CustomError> {
+        let hasher = ordered_hasher(config.hasher, "hasher")?;
+        let leaf_count = leaves.len();
+        let depth = config.depth_for(leaf_count)?;
+        let mut nodes = leaves.iter().map(leaf_node).collect::<Result<Vec<_>, _>>()?;
+        for index in leaf_count..1usize << depth {
+            nodes.push(leaf_node(&padding_leaf::<N>(config.padding, index)?)?);
+        }
+
+        let tree = MerkleTree::with_hasher(nodes, &hasher)?;
+        let root = commit_root(&tree.root(), leaf_count, depth as usize, hasher)?;
+        Ok(Self { tree, leaves, root })
+    }
+
+    /// Hashes credential values into leaves, see [`hash_values`], and builds a tree over them.
+    pub fn from_values(values: &[JsonValue], mode: ParseMode, config: &MerkleConfig) -> Result<Self, CustomError> {
+        Self::new(hash_values::<N>(values, mode)?, config)
+    }
+
+    /// The root to sign, committing to the leaf count, the depth and the hasher.
+    pub fn root(&self) -> Field<N> {
+        self.root
+    }
+
+    /// The root together with what it commits to.
+    pub fn merkle_root(&self) -> MerkleRoot<N> {
+        MerkleRoot { root: self.root, hasher: self.hasher(), leaf_count: self.leaf_count(), depth: self.depth() }
+    }
+
+    pub fn hasher(&self) -> MerkleHashAlgorithm {
+        self.tree.algorithm()
+    }
+
+    /// The root of the padded tree alone.
+    pub fn tree_root(&self) -> Field<N> {
+        self.tree.root()
+    }
+
+    pub fn leaf_count(&self) -> usize {
+        self.leaves.len()
+    }
+
+    pub fn depth(&self) -> usize {
+        self.tree.levels().len() - 1
+    }
+
+    /// The credential leaves, without padding and before [`leaf_node`].
+    pub fn leaves(&self) -> &[Field<N>] {
+        &self.leaves
+    }
+
+    /// Returns the proof of the leaf at `index`, which must not be a padded position.
+    pub fn get_proof(&self, index: usize) -> Result<MerkleProof<N>, CustomError> {
+        if index >= self.leaf_count() {
+            return Err(CustomError::IndexOutOfRange { index, len: self.leaf_count() });
+        }
+        let siblings = self.tree.get_proof(index)?;
+        let directions = (0..siblings.len()).map(|level| (index >> level) & 1 == 1).collect();
+        Ok(MerkleProof { index, leaf_count: self.leaf_count(), hasher: self.hasher(), siblings, directions })
+    }
+
+    /// Returns one proof of all the leaves at `indices`, sharing the nodes their paths have in
+    /// common. The indices may come in any order and are proven in ascending order.
+    pub fn get_multiproof(&self, indices: &[usize]) -> Result<MerkleMultiproof<N>, CustomError> {
+        let mut indices = indices.to_vec();
+        indices.sort_unstable();
+        indices.dedup();
+        if indices.is_empty() {
+            return Err(CustomError::parse("indices", "Expected at least one index"));
+        }
+        if let Some(&index) = indices.iter().find(|&&index| index >= self.leaf_count()) {
+            return Err(CustomError::IndexOutOfRange { index, len: self.leaf_count() });
+        }
+
+        let levels = self.tree.levels();
+        let mut known = indices.clone();
+        let mut siblings = Vec::new();
+        for level in &levels[..self.depth()] {
+            let mut i = 0;
+            while i < known.len() {
+                let index = known[i];
+                if index & 1 == 0 && known.get(i + 1) == Some(&(index + 1)) {
+                    i += 1;
+                } else {
+                    siblings.push(level[index ^ 1]);
+                }
+                i += 1;
+            }
+            known = known.iter().map(|index| index >> 1).collect();
+            known.dedup();
+        }
+        Ok(MerkleMultiproof { indices, leaf_count: self.leaf_count(), depth: self.depth(), hasher: self.hasher(), siblings })
+    }
+}
+
+/// The root of a [`CredentialTree`] and the parameters it commits to.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct MerkleRoot<N: NetworkNative> {
+    pub root: Field<N>,
+    pub hasher: MerkleHashAlgorithm,
+    pub leaf_count: usize,
+    pub depth: usize,
+}
+
+/// The proof of one leaf of a [`CredentialTree`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct MerkleProof<N: NetworkNative> {
+    pub index: usize,
+    pub leaf_count: usize,
+    pub hasher: MerkleHashAlgorithm,
+    /// The sibling of each level, from the leaves up. There is one per level of the tree, and
+    /// the root commits to their number.
+    pub siblings: Vec<Field<N>>,
+    /// Whether the node is the right child at each level, the bits of `index`.
+    pub directions: Vec<bool>,
+}
+
+impl<N: NetworkNative> MerkleProof<N> {
+    /// Recomputes the root, including the leaf count and the depth, from `leaf`.
+    pub fn compute_root(&self, leaf: &Field<N>) -> Result<Field<N>, CustomError> {
+        let hasher = ordered_hasher(self.hasher, "proof.hasher")?;
+        check_depth(self.siblings.len(), "proof.siblings")?;
+        let capacity = 1usize << self.siblings.len();
+        if self.leaf_count > capacity {
+            return Err(CustomError::IndexOutOfRange { index: self.leaf_count - 1, len: capacity });
+        }
+        if self.index >= self.leaf_count {
+            return Err(CustomError::IndexOutOfRange { index: self.index, len: self.leaf_count });
+        }
+        let directions_match = self.directions.len() == self.siblings.len()
+            && self.directions.iter().enumerate().all(|(level, right)| ((self.index >> level) & 1 == 1) == *right);
+        if !directions_match {
+            return Err(CustomError::parse("proof.directions", format!("Directions do not match index {}", self.index)));
+        }
+
+        let mut node = leaf_node(leaf)?;
+        for (sibling, right) in self.siblings.iter().zip(&self.directions) {
+            node = match right {
+                true => hasher.hash_pair(sibling, &node)?,
+                false => hasher.hash_pair(&node, sibling)?,
+            };
+        }
+        commit_root(&node, self.leaf_count, self.siblings.len(), hasher)
+    }
+
+    /// Returns whether `leaf` is in the tree with the committed `root`.
+    pub fn verify(&self, root: &Field<N>, leaf: &Field<N>) -> Result<bool, CustomError> {
+        Ok(&self.compute_root(leaf)? == root)
+    }
+}
+
+/// The proof of several leaves of a [`CredentialTree`], where a node is only given once even if
+/// several paths need it, and not at all when it can be computed from the proven leaves.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct MerkleMultiproof<N: NetworkNative> {
+    /// The proven leaf indices, strictly ascending.
+    pub indices: Vec<usize>,
+    pub leaf_count: usize,
+    pub depth: usize,
+    pub hasher: MerkleHashAlgorithm,
+    /// The missing siblings, level by level from the leaves up and left to right in each level.
+    pub siblings: Vec<Field<N>>,
+}
+
+impl<N: NetworkNative> MerkleMultiproof<N> {
+    /// Recomputes the root, including the leaf count and the depth, from the `leaves` at `indices`.
+    pub fn compute_root(&self, leaves: &[Field<N>]) -> Result<Field<N>, CustomError> {
+        let hasher = ordered_hasher(self.hasher, "proof.hasher")?;
+        check_depth(self.depth, "proof.depth")?;
+        let capacity = 1usize << self.depth;
+        if self.leaf_count > capacity {
+            return Err(CustomError::IndexOutOfRange { index: self.leaf_count - 1, len: capacity });
+        }
+        if self.indices.is_empty() || !self.indices.windows(2).all(|pair| pair[0] < pair[1]) {
+            return Err(CustomError::parse("proof.indices", "Expected strictly ascending indices"));
+        }
+        if let Some(&index) = self.indices.iter().find(|&&index| index >= self.leaf_count) {
+            return Err(CustomError::IndexOutOfRange { index, len: self.leaf_count });
+        }
+        if leaves.len() != self.indices.len() {
+            return Err(CustomError::parse("leaves", format!("Expected {} leaves, found {}", self.indices.len(), leaves.len())));
+        }
+
+        let mut siblings = self.siblings.iter();
+        let mut next_sibling = || siblings.next().copied()
+            .ok_or_else(|| CustomError::parse("proof.siblings", "Not enough siblings"));
+        let mut nodes = self.indices.iter().zip(leaves)
+            .map(|(&index, leaf)| Ok((index, leaf_node(leaf)?)))
+            .collect::<Result<Vec<(usize, Field<N>)>, CustomError>>()?;
+        for _ in 0..self.depth {
+            let mut parents = Vec::with_capacity(nodes.len());
+            let mut i = 0;
+            while i < nodes.len() {
+                let (index, node) = nodes[i];
+                let (left, right) = match nodes.get(i + 1) {
+                    Some(&(next, right)) if index & 1 == 0 && next == index + 1 => {
+                        i += 1;
+                        (node, right)
+                    }
+                    _ if index & 1 == 0 => (node, next_sibling()?),
+                    _ => (next_sibling()?, node),
+                };
+                parents.push((index >> 1, hasher.hash_pair(&left, &right)?));
+                i += 1;
+            }
+            nodes = parents;
+        }
+        if next_sibling().is_ok() {
+            return Err(CustomError::parse("proof.siblings", "Too many siblings"));
+        }
+        commit_root(&nodes[0].1, self.leaf_count, self.depth, hasher)
+    }
+
+    /// Returns whether all the `leaves` are in the tree with the committed `root`.
+    pub fn verify(&self, root: &Field<N>, leaves: &[Field<N>]) -> Result<bool, CustomError> {
+        Ok(&self.compute_root(leaves)? == root)
+    }
+}
+
+/// Computes `Poseidon2::hash_to_field([tree_root, leaf_count as field, depth as field, hasher as field])`.
+pub fn commit_root<N: NetworkNative>(tree_root: &Field<N>, leaf_count: usize, depth: usize, hasher: MerkleHashAlgorithm) -> Result<Field<N>, CustomError> {
+    hash_to_field_psd2(field_array(&[
+        *tree_root,
+        Field::from_u64(leaf_count as u64),
+        Field::from_u64(depth as u64),
+        Field::from_u64(hasher as u64),
+    ]))
+}
+
+/// Computes `Poseidon2::hash_to_field([leaf])`, the node a leaf enters the tree as.
+///
+/// A `[field; 1]` encodes apart from the `[field; 2]` of a node pair, so leaves and internal
+/// nodes are hashed under different domains.
+pub fn leaf_node<N: NetworkNative>(leaf: &Field<N>) -> Result<Field<N>, CustomError> {
+    hash_to_field_psd2(field_array(&[*leaf]))
+}
+
+fn padding_leaf<N: NetworkNative>(padding: MerklePadding, index: usize) -> Result<Field<N>, CustomError> {
+    match padding {
+        MerklePadding::Zero => Ok(Field::zero()),
+        MerklePadding::Sentinel => hash_to_field_psd2(field_array(&[hash_string::<N>("zpass.merkle.padding")?, Field::from_u64(index as u64)])),
+        MerklePadding::Exact => Err(CustomError::parse("leaves", "Exact trees are not padded")),
+    }
+}
+
+/// Rejects the sum hasher, under which siblings commute and a leaf can be proven at its
+/// sibling's position.
+fn ordered_hasher(hasher: MerkleHashAlgorithm, path: &str) -> Result<MerkleHashAlgorithm, CustomError> {
+    match hasher.is_ordered() {
+        true => Ok(hasher),
+        false => Err(CustomError::parse(path, "Credential trees need an ordered hasher, the sum hasher does not bind leaves to their positions")),
+    }
+}
+
+fn check_depth(depth: usize, path: &str) -> Result<(), CustomError> {
+    match depth > MAX_MERKLE_DEPTH as usize {
+        true => Err(CustomError::parse(path, format!("Expected a depth of at most {}, found {}", MAX_MERKLE_DEPTH, depth))),
+        false => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    // Define the network type for the tests
+    type N = TestnetV0;
+
+    fn values(count: usize) -> Vec<JsonValue> {
+        (0..count).map(|i| json!(format!("{}u32", i + 1))).collect()
+    }
+
+    #[test]
+    fn test_credential_tree_of_any_size() {
+        for count in [1, 5, 16, 20, 33] {
+            let tree = CredentialTree::<N>::from_values(&values(count), ParseMode::Strict, &MerkleConfig::default()).unwrap();
+            assert_eq!(tree.leaf_count(), count);
+            assert_eq!(1 << tree.depth(), count.next_power_of_two());
+            for (index, leaf) in tree.leaves().iter().enumerate() {
+                assert!(tree.get_proof(index).unwrap().verify(&tree.root(), leaf).unwrap());
+            }
+            assert!(matches!(tree.get_proof(count), Err(CustomError::IndexOutOfRange { .. })));
+        }
+
+        // The padded tree is built over the leaf nodes, and the root commits to its shape
+        let config = MerkleConfig::with_depth(3, MerklePadding::Zero).unwrap();
+        let tree = CredentialTree::<N>::from_values(&values(6), ParseMode::Strict, &config).unwrap();
+        let mut leaves = hash_values_size_8::<N>(&values(6), ParseMode::Strict).unwrap();
+        assert_eq!(tree.leaves(), &leaves[..6]);
+        leaves = leaves.iter().map(leaf_node).collect::<Result<_, _>>().unwrap();
+        let expected = MerkleTree::<N>::with_hasher(leaves, &Poseidon2Hasher).unwrap();
+        assert_eq!(tree.tree_root(), expected.root());
+        assert_eq!(tree.root(), commit_root(&expected.root(), 6, 3, MerkleHashAlgorithm::Poseidon2).unwrap());
+    }
+
+    #[test]
+    fn test_leaf_count_is_committed() {
+        let leaf = hash_values::<N>(&values(1), ParseMode::Strict).unwrap()[0];
+        let padded = CredentialTree::<N>::new(vec![leaf], &MerkleConfig::with_depth(1, MerklePadding::Zero).unwrap()).unwrap();
+        let zero_leaf = CredentialTree::<N>::new(vec![leaf, Field::zero()], &MerkleConfig::default()).unwrap();
+        assert_eq!(padded.tree_root(), zero_leaf.tree_root());
+        assert_ne!(padded.root(), zero_leaf.root());
+
+        // A proof cannot claim a padded position or another leaf count
+        let mut proof = zero_leaf.get_proof(1).unwrap();
+        assert!(proof.verify(&zero_leaf.root(), &Field::zero()).unwrap());
+        assert!(!proof.verify(&padded.root(), &Field::zero()).unwrap());
+        proof.leaf_count = 1;
+        assert!(proof.verify(&padded.root(), &Field::zero()).is_err());
+
+        let json = serde_json::to_value(padded.get_proof(0).unwrap()).unwrap();
+        assert_eq!(json["leaf_count"], 1);
+        let proof: MerkleProof<N> = serde_json::from_value(json).unwrap();
+        assert!(proof.verify(&padded.root(), &leaf).unwrap());
+    }
+
+    #[test]
+    fn test_positions_and_depth_are_bound() {
+        let leaf = hash_values::<N>(&values(1), ParseMode::Strict).unwrap()[0];
+        let tree = CredentialTree::<N>::new(vec![leaf, Field::zero()], &MerkleConfig::default()).unwrap();
+        assert_eq!(tree.hasher(), MerkleHashAlgorithm::Poseidon2);
+
+        // `0field` cannot be proven at index 0 by swapping it with its sibling
+        let mut forged = tree.get_proof(0).unwrap();
+        forged.siblings[0] = leaf_node(&leaf).unwrap();
+        assert!(!forged.verify(&tree.root(), &Field::zero()).unwrap());
+
+        // The sum hasher would accept that swap, so credential trees refuse it
+        let sum = MerkleConfig::default().with_hasher(MerkleHashAlgorithm::Sum);
+        assert_eq!(CredentialTree::<N>::new(vec![leaf, Field::zero()], &sum).unwrap_err().path(), Some("hasher"));
+        forged.hasher = MerkleHashAlgorithm::Sum;
+        assert_eq!(forged.verify(&tree.root(), &Field::zero()).unwrap_err().path(), Some("proof.hasher"));
+
+        // An internal node cannot be proven as a leaf of a shallower tree
+        let leaves = hash_values::<N>(&values(4), ParseMode::Strict).unwrap();
+        let tree = CredentialTree::<N>::new(leaves, &MerkleConfig::default()).unwrap();
+        let levels = tree.tree.levels();
+        for leaf_count in [1, 2] {
+            let short = MerkleProof { index: 0, leaf_count, hasher: tree.hasher(), siblings: vec![levels[1][1]], directions: vec![false] };
+            assert!(!short.verify(&tree.root(), &levels[1][0]).unwrap());
+        }
+        let internal = MerkleTree::<N>::with_hasher(levels[1].clone(), &Poseidon2Hasher).unwrap();
+        assert_eq!(internal.root(), tree.tree_root());
+        assert_ne!(commit_root(&internal.root(), 2, 1, tree.hasher()).unwrap(), tree.root());
+    }
+
+    #[test]
+    fn test_padding_policies() {
+        let leaves = hash_values::<N>(&values(3), ParseMode::Strict).unwrap();
+        let zero = CredentialTree::<N>::new(leaves.clone(), &MerkleConfig::new(MerklePadding::Zero)).unwrap();
+        let sentinel = CredentialTree::<N>::new(leaves.clone(), &MerkleConfig::new(MerklePadding::Sentinel)).unwrap();
+        assert_ne!(zero.root(), sentinel.root());
+        assert_eq!(sentinel.leaves(), leaves.as_slice());
+        assert!(sentinel.get_proof(2).unwrap().verify(&sentinel.root(), &leaves[2]).unwrap());
+
+        assert!(CredentialTree::<N>::new(leaves.clone(), &MerkleConfig::new(MerklePadding::Exact)).is_err());
+        assert!(CredentialTree::<N>::new(leaves[..2].to_vec(), &MerkleConfig::new(MerklePadding::Exact)).is_ok());
+        assert!(matches!(CredentialTree::<N>::new(leaves, &MerkleConfig::with_depth(1, MerklePadding::Zero).unwrap()), Err(CustomError::IndexOutOfRange { .. })));
+        assert!(CredentialTree::<N>::new(Vec::new(), &MerkleConfig::default()).is_err());
+        assert!(MerkleConfig::with_depth(MAX_MERKLE_DEPTH + 1, MerklePadding::Zero).is_err());
+    }
+
+    #[test]
+    fn test_ordered_hashers() {
+        let leaves = hash_values::<N>(&values(5), ParseMode::Strict).unwrap();
+        let mut roots = Vec::new();
+        for hasher in [MerkleHashAlgorithm::Poseidon2, MerkleHashAlgorithm::Poseidon4, MerkleHashAlgorithm::Bhp256] {
+            let tree = CredentialTree::<N>::new(leaves.clone(), &MerkleConfig::default().with_hasher(hasher)).unwrap();
+            assert_eq!(tree.merkle_root().hasher, hasher);
+            for (index, leaf) in leaves.iter().enumerate() {
+                let proof = tree.get_proof(index).unwrap();
+                assert_eq!(proof.directions.len(), tree.depth());
+                assert!(proof.verify(&tree.root(), leaf).unwrap());
+
+                // The proof only holds under the hasher it was made with
+                let mut other = proof.clone();
+                other.hasher = if hasher == MerkleHashAlgorithm::Poseidon2 { MerkleHashAlgorithm::Poseidon4 } else { MerkleHashAlgorithm::Poseidon2 };
+                assert!(!other.verify(&tree.root(), leaf).unwrap());
+            }
+            roots.push(tree.root());
+        }
+        roots.dedup();
+        assert_eq!(roots.len(), 3);
+
+        // Swapped siblings only collide under the sum hasher
+        let (a, b) = (leaves[0], leaves[1]);
+        assert_eq!(SumHasher.hash_pair(&a, &b).unwrap(), MerkleHasher::<N>::hash_pair(&SumHasher, &b, &a).unwrap());
+        for hasher in [MerkleHashAlgorithm::Poseidon2, MerkleHashAlgorithm::Poseidon4, MerkleHashAlgorithm::Bhp256] {
+            assert!(hasher.is_ordered());
+            assert_ne!(hasher.hash_pair(&a, &b).unwrap(), MerkleHasher::<N>::hash_pair(&hasher, &b, &a).unwrap());
+        }
+        // The ordered hashers hash the Leo array `[a, b]`, like the `hash.*` instructions do
+        let pair = Value::<N>::from_str(&format!("[{a}, {b}]")).unwrap();
+        assert_eq!(Poseidon2Hasher.hash_pair(&a, &b).unwrap(), N::hash_psd2(&pair.to_fields().unwrap()).unwrap());
+        assert_eq!(Poseidon4Hasher.hash_pair(&a, &b).unwrap(), N::hash_psd4(&pair.to_fields().unwrap()).unwrap());
+        assert_eq!(Bhp256Hasher.hash_pair(&a, &b).unwrap(), N::hash_bhp256(&pair.to_bits_le()).unwrap());
+        assert_ne!(Poseidon2Hasher.hash_pair(&a, &b).unwrap(), N::hash_psd2(&[a, b]).unwrap());
+
+        // Direction bits must agree with the index
+        let tree = CredentialTree::<N>::new(leaves.clone(), &MerkleConfig::default().with_hasher(MerkleHashAlgorithm::Poseidon2)).unwrap();
+        let mut proof = tree.get_proof(1).unwrap();
+        proof.directions[0] = false;
+        assert_eq!(proof.verify(&tree.root(), &leaves[1]).unwrap_err().path(), Some("proof.directions"));
+
+        let ordered = MerkleTree::<N>::with_hasher(leaves[..4].to_vec(), &Bhp256Hasher).unwrap();
+        assert!(ordered.verify_proof_at(2, leaves[2], &ordered.get_proof(2).unwrap()).unwrap());
+        assert!(!ordered.verify_proof_at(3, leaves[2], &ordered.get_proof(2).unwrap()).unwrap());
+        assert!(ordered.verify_proof(leaves[2], &ordered.get_proof(2).unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_multiproofs() {
+        let leaves = hash_values::<N>(&values(20), ParseMode::Strict).unwrap();
+        let tree = CredentialTree::<N>::new(leaves.clone(), &MerkleConfig::default()).unwrap();
+
+        let proof = tree.get_multiproof(&[19, 5, 0, 1, 5]).unwrap();
+        assert_eq!(proof.indices, vec![0, 1, 5, 19]);
+        let proven = [leaves[0], leaves[1], leaves[5], leaves[19]];
+        assert!(proof.verify(&tree.root(), &proven).unwrap());
+        let separate: usize = proof.indices.iter().map(|&index| tree.get_proof(index).unwrap().siblings.len()).sum();
+        assert_eq!(separate, 20);
+        assert_eq!(proof.siblings.len(), 8);
+
+        // A single leaf needs its whole path, and every leaf of a full tree needs none
+        for index in [0, 7, 19] {
+            let single = tree.get_multiproof(&[index]).unwrap();
+            assert_eq!(single.siblings, tree.get_proof(index).unwrap().siblings);
+            assert!(single.verify(&tree.root(), &[leaves[index]]).unwrap());
+        }
+        let full = CredentialTree::<N>::new(leaves[..16].to_vec(), &MerkleConfig::default()).unwrap();
+        let all = full.get_multiproof(&(0..16).collect::<Vec<_>>()).unwrap();
+        assert!(all.siblings.is_empty());
+        assert!(all.verify(&full.root(), &leaves[..16]).unwrap());
+
+        // The internal nodes of a level are not the leaves of a shallower tree
+        let level = full.tree.levels()[1].clone();
+        let shallow = MerkleMultiproof { indices: (0..8).collect(), leaf_count: 8, depth: 3, hasher: full.hasher(), siblings: Vec::new() };
+        assert!(!shallow.verify(&full.root(), &level).unwrap());
+
+        // Under the default hasher, leaves are bound to their positions
+        let mut swapped = proven;
+        swapped.swap(0, 1);
+        assert!(!proof.verify(&tree.root(), &swapped).unwrap());
+        assert!(proof.verify(&tree.root(), &proven[..3]).is_err());
+
+        let mut tampered = proof.clone();
+        tampered.hasher = MerkleHashAlgorithm::Sum;
+        assert_eq!(tampered.verify(&tree.root(), &proven).unwrap_err().path(), Some("proof.hasher"));
+
+        let mut tampered = proof.clone();
+        tampered.indices.swap(0, 1);
+        assert_eq!(tampered.verify(&tree.root(), &proven).unwrap_err().path(), Some("proof.indices"));
+        let mut tampered = proof.clone();
+        tampered.siblings.pop();
+        assert_eq!(tampered.verify(&tree.root(), &proven).unwrap_err().path(), Some("proof.siblings"));
+        let mut tampered = proof.clone();
+        tampered.siblings.push(Field::zero());
+        assert_eq!(tampered.verify(&tree.root(), &proven).unwrap_err().path(), Some("proof.siblings"));
+        let mut tampered = proof.clone();
+        tampered.leaf_count = 19;
+        assert!(matches!(tampered.verify(&tree.root(), &proven), Err(CustomError::IndexOutOfRange { .. })));
+
+        assert!(matches!(tree.get_multiproof(&[3, 20]), Err(CustomError::IndexOutOfRange { .. })));
+        assert!(tree.get_multiproof(&[]).is_err());
+
+        let json = serde_json::to_value(&proof).unwrap();
+        let proof: MerkleMultiproof<N> = serde_json::from_value(json).unwrap();
+        assert!(proof.verify(&tree.root(), &proven).unwrap());
+    }
+}
@@ -0,0 +1,355 @@
+use super::*;
+use snarkvm_console::program::{LiteralType, PlaintextType, RecordType, StructType};
+
+/// Leo members as `(name, type as written)`.
+type LeoMembers<'a> = Vec<(&'a str, String)>;
+
+/// Extracts every struct and record definition from a Leo source file as encoding schemas.
+///
+/// Records are returned with their explicit `owner` member first, exactly as declared,
+/// so `ZPass` and `FullCredentials` come out with the member order the program hashes.
+/// Members typed by another struct of the same file or by `[T; N]` arrays are resolved
+/// into nested schemas.
+pub fn schemas_from_leo(source: &str) -> Result<IndexMap<String, CredentialSchema>, CustomError> {
+    let tokens = tokenize_leo(source);
+    let mut definitions: IndexMap<&str, LeoMembers> = IndexMap::new();
+    let mut i = 0;
+
+    while i < tokens.len() {
+        let is_definition = matches!(tokens[i], LeoToken::Word("struct") | LeoToken::Word("record"))
+            && matches!(tokens.get(i + 2), Some(LeoToken::Symbol('{')));
+        if !is_definition {
+            i += 1;
+            continue;
+        }
+        let LeoToken::Word(name) = tokens[i + 1] else {
+            i += 1;
+            continue;
+        };
+
+        let (members, next) = parse_leo_members(name, &tokens, i + 3)?;
+        definitions.entry(name).or_insert(members);
+        i = next;
+    }
+
+    let mut schemas = IndexMap::with_capacity(definitions.len());
+    for name in definitions.keys() {
+        schemas.insert(name.to_string(), resolve_leo_schema(name, &definitions, &mut Vec::new())?);
+    }
+    Ok(schemas)
+}
+
+fn resolve_leo_schema<'a>(name: &'a str, definitions: &IndexMap<&'a str, LeoMembers<'a>>, stack: &mut Vec<&'a str>) -> Result<CredentialSchema, CustomError> {
+    if stack.contains(&name) {
+        return Err(CustomError::parse(name, "Struct is recursive"));
+    }
+    stack.push(name);
+    let mut members = Vec::new();
+    for (member, ty) in &definitions[name] {
+        let member_type = resolve_leo_type(ty, definitions, stack).ok_or_else(|| CustomError::UnsupportedType {
+            member: format!("{}.{}", name, member),
+            ty: ty.clone(),
+        })??;
+        members.push(SchemaMember::required(member, member_type));
+    }
+    stack.pop();
+    Ok(CredentialSchema::new(name, members))
+}
+
+/// Resolves a Leo type; `None` if the type is neither a literal, an array nor a local struct.
+fn resolve_leo_type<'a>(ty: &str, definitions: &IndexMap<&'a str, LeoMembers<'a>>, stack: &mut Vec<&'a str>) -> Option<Result<MemberType, CustomError>> {
+    if let Ok(literal_type) = LiteralType::from_str(ty) {
+        return Some(Ok(MemberType::Literal(literal_type)));
+    }
+    if let Some(inner) = ty.strip_prefix('[').and_then(|ty| ty.strip_suffix(']')) {
+        let (element, length) = inner.rsplit_once(';')?;
+        let length = length.trim().trim_end_matches("u32").parse::<u32>().ok()?;
+        return Some(resolve_leo_type(element.trim(), definitions, stack)?.map(|element| MemberType::array(element, length)));
+    }
+    let (name, _) = definitions.get_key_value(ty)?;
+    Some(resolve_leo_schema(name, definitions, stack).map(MemberType::structure))
+}
+
+/// Extracts every struct and record definition from compiled Aleo instructions as encoding schemas.
+///
+/// Record schemas start with the implicit `owner: address` member followed by the record entries.
+pub fn schemas_from_aleo<N: NetworkNative>(source: &str) -> Result<IndexMap<String, CredentialSchema>, CustomError> {
+    let mut structs: IndexMap<String, StructType<N>> = IndexMap::new();
+    let mut records: Vec<RecordType<N>> = Vec::new();
+    let mut offset = 0;
+
+    for line in source.split_inclusive('\n') {
+        let trimmed = line.trim_start();
+        let start = offset + line.len() - trimmed.len();
+        offset += line.len();
+
+        if trimmed.starts_with("struct ") {
+            let (_, struct_type) = StructType::<N>::parse(&source[start..])
+                .map_err(|e| CustomError::parse("source", format!("Failed to parse struct definition: {}", e)))?;
+            structs.entry(struct_type.name().to_string()).or_insert(struct_type);
+        } else if trimmed.starts_with("record ") {
+            let (_, record_type) = RecordType::<N>::parse(&source[start..])
+                .map_err(|e| CustomError::parse("source", format!("Failed to parse record definition: {}", e)))?;
+            records.push(record_type);
+        }
+    }
+
+    let mut schemas = IndexMap::new();
+    for record_type in records {
+        let name = record_type.name().to_string();
+        let mut members = vec![SchemaMember::required("owner", LiteralType::Address)];
+        for (entry, entry_type) in record_type.entries() {
+            let member_type = resolve_aleo_type(&name, &entry.to_string(), entry_type.plaintext_type(), &structs, &mut Vec::new())?;
+            members.push(SchemaMember::required(&entry.to_string(), member_type));
+        }
+        schemas.entry(name.clone()).or_insert_with(|| CredentialSchema::new(&name, members));
+    }
+    for name in structs.keys() {
+        let schema = resolve_aleo_schema(name, &structs, &mut Vec::new())?;
+        schemas.entry(name.clone()).or_insert(schema);
+    }
+
+    Ok(schemas)
+}
+
+fn resolve_aleo_schema<N: NetworkNative>(name: &str, structs: &IndexMap<String, StructType<N>>, stack: &mut Vec<String>) -> Result<CredentialSchema, CustomError> {
+    if stack.iter().any(|visited| visited == name) {
+        return Err(CustomError::parse(name, "Struct is recursive"));
+    }
+    stack.push(name.to_string());
+    let mut members = Vec::new();
+    for (member, plaintext_type) in structs[name].members() {
+        let member_type = resolve_aleo_type(name, &member.to_string(), plaintext_type, structs, stack)?;
+        members.push(SchemaMember::required(&member.to_string(), member_type));
+    }
+    stack.pop();
+    Ok(CredentialSchema::new(name, members))
+}
+
+fn resolve_aleo_type<N: NetworkNative>(definition: &str, member: &str, plaintext_type: &PlaintextType<N>, structs: &IndexMap<String, StructType<N>>, stack: &mut Vec<String>) -> Result<MemberType, CustomError> {
+    match plaintext_type {
+        PlaintextType::Literal(literal_type) => Ok(MemberType::Literal(*literal_type)),
+        PlaintextType::Array(array_type) => {
+            let element = resolve_aleo_type(definition, member, array_type.next_element_type(), structs, stack)?;
+            Ok(MemberType::array(element, **array_type.length()))
+        }
+        PlaintextType::Struct(name) if structs.contains_key(&name.to_string()) => {
+            resolve_aleo_schema(&name.to_string(), structs, stack).map(MemberType::structure)
+        }
+        other => Err(CustomError::UnsupportedType {
+            member: format!("{}.{}", definition, member),
+            ty: other.to_string(),
+        }),
+    }
+}
+
+impl CredentialSchema {
+    /// Returns the schema of the struct or record called `name` in a Leo source file.
+    pub fn from_leo(source: &str, name: &str) -> Result<Self, CustomError> {
+        schemas_from_leo(source)?.shift_remove(name)
+            .ok_or_else(|| CustomError::parse(name, "No struct or record with this name found in the Leo source"))
+    }
+
+    /// Returns the schema of the struct or record called `name` in compiled Aleo instructions.
+    pub fn from_aleo<N: NetworkNative>(source: &str, name: &str) -> Result<Self, CustomError> {
+        schemas_from_aleo::<N>(source)?.shift_remove(name)
+            .ok_or_else(|| CustomError::parse(name, "No struct or record with this name found in the Aleo program"))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LeoToken<'a> {
+    Word(&'a str),
+    Symbol(char),
+}
+
+/// Splits Leo source into words and punctuation, dropping whitespace and comments.
+fn tokenize_leo(source: &str) -> Vec<LeoToken<'_>> {
+    let is_word_char = |c: char| c.is_alphanumeric() || matches!(c, '_' | '.' | '/');
+    let mut tokens = Vec::new();
+    let mut rest = source;
+
+    while let Some(c) = rest.chars().next() {
+        if c.is_whitespace() {
+            rest = &rest[c.len_utf8()..];
+        } else if rest.starts_with("//") {
+            rest = rest.find('\n').map_or("", |end| &rest[end..]);
+        } else if rest.starts_with("/*") {
+            rest = rest[2..].find("*/").map_or("", |end| &rest[end + 4..]);
+        } else if is_word_char(c) {
+            let end = rest.find(|c: char| !is_word_char(c)).unwrap_or(rest.len());
+            tokens.push(LeoToken::Word(&rest[..end]));
+            rest = &rest[end..];
+        } else {
+            tokens.push(LeoToken::Symbol(c));
+            rest = &rest[c.len_utf8()..];
+        }
+    }
+
+    tokens
+}
+
+/// Parses `name: type` members up to the closing brace that starts at `tokens[start]`.
+/// Returns the members with their type as written and the index after the closing brace.
+fn parse_leo_members<'a>(definition: &str, tokens: &[LeoToken<'a>], start: usize) -> Result<(LeoMembers<'a>, usize), CustomError> {
+    let mut members = Vec::new();
+    let mut i = start;
+
+    loop {
+        match tokens.get(i) {
+            Some(LeoToken::Symbol('}')) => return Ok((members, i + 1)),
+            Some(LeoToken::Symbol(',')) => {
+                i += 1;
+                continue;
+            }
+            None => return Err(CustomError::parse(definition, "Unterminated definition")),
+            _ => {}
+        }
+
+        // Leo records may carry an explicit visibility in front of the member name
+        if let Some(LeoToken::Word("public" | "private" | "constant")) = tokens.get(i) {
+            i += 1;
+        }
+        let (Some(LeoToken::Word(member)), Some(LeoToken::Symbol(':'))) = (tokens.get(i), tokens.get(i + 1)) else {
+            return Err(CustomError::parse(definition, "Expected `name: type`"));
+        };
+        i += 2;
+
+        // The type runs until the next comma or closing brace outside of brackets
+        let mut depth = 0usize;
+        let mut ty = String::new();
+        while let Some(token) = tokens.get(i) {
+            match token {
+                LeoToken::Symbol(',' | '}') if depth == 0 => break,
+                LeoToken::Symbol('[') => depth += 1,
+                LeoToken::Symbol(']') => depth = depth.saturating_sub(1),
+                _ => {}
+            }
+            match token {
+                LeoToken::Word(word) => ty.push_str(word),
+                LeoToken::Symbol(';') => ty.push_str("; "),
+                LeoToken::Symbol(symbol) => ty.push(*symbol),
+            }
+            i += 1;
+        }
+
+        members.push((*member, ty));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Define the network type for the tests
+    type N = TestnetV0;
+
+    const VERIFY_POSEIDON2_ZPASS: &str = include_str!("../../programs/verify_poseidon2_zpass/src/main.leo");
+    const ZPASS_INVALIDATE_HIDING: &str = include_str!("../../programs/zpass_invalidate_hiding/src/main.leo");
+
+    const ALEO_PROGRAM: &str = r"
+program verify_poseidon2_zpass.aleo;
+
+record ZPass:
+    owner as address.private;
+    issuer as address.private;
+    dob as u32.private;
+    nationality as field.private;
+    expiry as u32.private;
+
+struct Credentials:
+    issuer as address;
+    subject as address;
+    dob as u32;
+    nationality as field;
+    expiry as u32;
+
+
+function issue:
+    input r0 as signature.private;
+    input r1 as Credentials.private;
+    hash.psd2 r1 into r2 as field;
+";
+
+    fn member_names(schema: &CredentialSchema) -> Vec<&str> {
+        schema.members().iter().map(|member| member.name.as_str()).collect()
+    }
+
+    #[test]
+    fn test_schemas_from_leo() {
+        let schemas = schemas_from_leo(VERIFY_POSEIDON2_ZPASS).unwrap();
+        let names: Vec<&str> = schemas.keys().map(|k| k.as_str()).collect();
+        assert_eq!(names, ["ZPass", "PrivateCredentials", "PublicCredentials", "FullCredentials"]);
+
+        let full = &schemas["FullCredentials"];
+        assert_eq!(member_names(full), ["issuer", "subject", "dob", "nationality", "expiry", "salt"]);
+        assert_eq!(full.members()[2].member_type, MemberType::Literal(LiteralType::U32));
+        assert_eq!(full.members()[5].member_type, MemberType::Literal(LiteralType::Scalar));
+        assert_eq!(member_names(&schemas["ZPass"]), ["owner", "issuer", "dob", "nationality", "expiry", "salt"]);
+    }
+
+    #[test]
+    fn test_schema_from_leo_hiding_program() {
+        let schema = CredentialSchema::from_leo(ZPASS_INVALIDATE_HIDING, "InvalidateZPass").unwrap();
+        assert_eq!(member_names(&schema), ["issuer", "subject", "dob", "nationality", "expiry", "salt"]);
+        assert_eq!(schema.members()[0].member_type, MemberType::Literal(LiteralType::Group));
+        assert!(CredentialSchema::from_leo(ZPASS_INVALIDATE_HIDING, "Missing").is_err());
+    }
+
+    #[test]
+    fn test_schemas_from_leo_skips_comments() {
+        let source = "program a.aleo { /* struct Hidden { a: u8 } */ // record Nope { owner: address }\n struct S { private a: u8, b: field, } }";
+        let schemas = schemas_from_leo(source).unwrap();
+        assert_eq!(schemas.len(), 1);
+        assert_eq!(member_names(&schemas["S"]), ["a", "b"]);
+    }
+
+    #[test]
+    fn test_sign_with_leo_schema() {
+        let schema = CredentialSchema::from_leo(VERIFY_POSEIDON2_ZPASS, "FullCredentials").unwrap();
+        let message = SignInboundMessage::from_json(serde_json::json!({
+            "issuer": "aleo1ekyuzclmcw3aj7qncsxxaapxem82mgrd8zadgrrvl5k705zx6q9s7usuqy",
+            "subject": "aleo14w44zfrehup9g894j7tgeyz5gsjuxn0nfn09vd2fvpznrg85rs8skywkte",
+            "dob": "20000101u32",
+            "nationality": "123field",
+            "expiry": "20300101u32",
+            "salt": "42scalar"
+        })).with_credential_schema(schema);
+        let private_key = "APrivateKey1zkp5LqRmm7535XfiX77VPQEgsS2Dj1B2DvH4QNP1UYrHEoR".to_string();
+        assert!(sign_message_with_logger::<N>(private_key, message, HashAlgorithm::POSEIDON2, &StdoutLogger).is_ok());
+    }
+
+    #[test]
+    fn test_schemas_from_leo_nested_types() {
+        let source = "program a.aleo {
+            struct Credentials { issuer: address, block: Addresses, limits: [[u8; 2]; 3] }
+            struct Addresses { home: address, delegates: [address; 4] }
+            struct Remote { zpass: verify_poseidon2_zpass.aleo/ZPass }
+        }";
+        let error = schemas_from_leo(source).unwrap_err();
+        assert!(matches!(error, CustomError::UnsupportedType { member, .. } if member == "Remote.zpass"));
+
+        let source = source.replace("struct Remote { zpass: verify_poseidon2_zpass.aleo/ZPass }", "");
+        let credentials = CredentialSchema::from_leo(&source, "Credentials").unwrap();
+        assert_eq!(credentials.members()[1].member_type.to_string(), "Addresses");
+        assert_eq!(credentials.members()[2].member_type, MemberType::array(MemberType::array(LiteralType::U8, 2), 3));
+        let MemberType::Struct { schema } = &credentials.members()[1].member_type else { panic!("expected a struct") };
+        assert_eq!(schema.members()[1].member_type, MemberType::array(LiteralType::Address, 4));
+    }
+
+    #[test]
+    fn test_schemas_from_aleo_nested_types() {
+        let source = "program a.aleo;\n\nstruct Inner:\n    a as u8;\n\nstruct Outer:\n    inner as Inner;\n    list as [field; 2u32];\n";
+        let outer = CredentialSchema::from_aleo::<N>(source, "Outer").unwrap();
+        assert_eq!(outer.members()[0].member_type.to_string(), "Inner");
+        assert_eq!(outer.members()[1].member_type, MemberType::array(LiteralType::Field, 2));
+    }
+
+    #[test]
+    fn test_schemas_from_aleo() {
+        let schemas = schemas_from_aleo::<N>(ALEO_PROGRAM).unwrap();
+        assert_eq!(member_names(&schemas["ZPass"]), ["owner", "issuer", "dob", "nationality", "expiry"]);
+        assert_eq!(member_names(&schemas["Credentials"]), ["issuer", "subject", "dob", "nationality", "expiry"]);
+        assert_eq!(schemas["Credentials"], CredentialSchema::from_leo(include_str!("../../programs/verify_poseidon2/src/main.leo"), "Credentials").unwrap());
+    }
+}
@@ -0,0 +1,121 @@
+use super::*;
+use snarkvm_console::program::ProgramID;
+
+/// The program, schema and schema version a domain-separated credential signature is bound to.
+///
+/// The network is taken from the network the credential is signed for. The domain is reduced to
+/// a single tag, the Poseidon2 hash of
+///
+/// ```leo
+/// struct DomainTag { program: address, network: u16, schema: field, version: u32 }
+/// ```
+///
+/// where `program` is the program address (`self.address` inside the program), `network` is
+/// the snarkVM network id and `schema` is [`hash_string`] of the schema id. The signed message
+/// is then the hash of `DomainMessage { domain: field, hash: field }` over the tag and the
+/// credential hash, using the credential hash algorithm. A signature made for one program,
+/// network or schema version does not verify for another.
+#[wasm_bindgen]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SigningDomain {
+    pub(crate) program: String,
+    pub(crate) schema: String,
+    pub(crate) version: u32,
+}
+
+impl SigningDomain {
+    /// Creates a domain for `program` (e.g. `verify_poseidon2_zpass.aleo`) and a schema id and version.
+    pub fn new(program: &str, schema: &str, version: u32) -> Self {
+        Self { program: program.to_string(), schema: schema.to_string(), version }
+    }
+
+    pub fn program(&self) -> &str {
+        &self.program
+    }
+
+    pub fn schema(&self) -> &str {
+        &self.schema
+    }
+
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    /// Returns the `DomainTag` struct the tag is hashed from.
+    pub fn tag_struct<N: NetworkNative>(&self) -> Result<Value<N>, CustomError> {
+        let program = ProgramID::<N>::from_str(&self.program)
+            .and_then(|program| program.to_address())
+            .map_err(|e| CustomError::parse("domain.program", e))?;
+        let schema = hash_string::<N>(&self.schema)?;
+
+        let mut members = IndexMap::with_capacity(4);
+        members.insert("program".to_string(), Plaintext::from(Literal::Address(program)));
+        members.insert("network".to_string(), Plaintext::from(Literal::U16(U16::new(N::ID))));
+        members.insert("schema".to_string(), Plaintext::from(Literal::Field(schema)));
+        members.insert("version".to_string(), Plaintext::from(Literal::U32(U32::new(self.version))));
+        generate_message_with_addresses_and_fields(Credential { data: members })
+    }
+
+    /// Returns the domain tag, `Poseidon2::hash_to_field(DomainTag { .. })`.
+    pub fn tag<N: NetworkNative>(&self) -> Result<Field<N>, CustomError> {
+        let tag = create_hash(self.tag_struct::<N>()?, HashAlgorithm::POSEIDON2)?;
+        Field::<N>::from_str(&tag).map_err(|e| CustomError::HashFailure(e.to_string()))
+    }
+
+    /// Mixes the domain tag into a credential hash computed with `algorithm`.
+    pub fn separate<N: NetworkNative>(&self, hash: &str, algorithm: HashAlgorithm) -> Result<String, CustomError> {
+        let hash = Field::<N>::from_str(hash).map_err(|e| CustomError::parse("hash", e))?;
+
+        let mut members = IndexMap::with_capacity(2);
+        members.insert("domain".to_string(), Plaintext::from(Literal::Field(self.tag::<N>()?)));
+        members.insert("hash".to_string(), Plaintext::from(Literal::Field(hash)));
+        create_hash(generate_message_with_addresses_and_fields(Credential { data: members })?, algorithm)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    // Define the network type for the tests
+    type N = TestnetV0;
+
+    const PRIVATE_KEY: &str = "APrivateKey1zkp5LqRmm7535XfiX77VPQEgsS2Dj1B2DvH4QNP1UYrHEoR";
+    const ISSUER: &str = "aleo1ekyuzclmcw3aj7qncsxxaapxem82mgrd8zadgrrvl5k705zx6q9s7usuqy";
+
+    fn message(domain: Option<SigningDomain>) -> SignInboundMessage {
+        SignInboundMessage::from_json(json!({ "issuer": ISSUER, "dob": "20000101u32" })).with_mode(ParseMode::Strict).with_domain(domain)
+    }
+
+    #[test]
+    fn test_domain_tag_matches_leo_struct() {
+        let domain = SigningDomain::new("verify_poseidon2_zpass.aleo", "FullCredentials", 1);
+        let program = ProgramID::<N>::from_str("verify_poseidon2_zpass.aleo").unwrap().to_address().unwrap();
+        let schema = hash_string::<N>("FullCredentials").unwrap();
+        let expected = Value::<N>::from_str(&format!("{{ program: {program}, network: 1u16, schema: {schema}, version: 1u32 }}")).unwrap();
+        assert_eq!(domain.tag_struct::<N>().unwrap(), expected);
+        assert_eq!(domain.tag::<N>().unwrap().to_string(), create_hash(expected, HashAlgorithm::POSEIDON2).unwrap());
+
+        // Every component changes the tag
+        let tag = domain.tag::<N>().unwrap();
+        assert_ne!(tag, SigningDomain::new("zpass_hiding.aleo", "FullCredentials", 1).tag::<N>().unwrap());
+        assert_ne!(tag, SigningDomain::new("verify_poseidon2_zpass.aleo", "FullCredentials", 2).tag::<N>().unwrap());
+        assert_ne!(tag.to_string(), domain.tag::<MainnetV0>().unwrap().to_string());
+        assert_eq!(SigningDomain::new("not a program", "FullCredentials", 1).tag::<N>().unwrap_err().path(), Some("domain.program"));
+    }
+
+    #[test]
+    fn test_domain_separated_signature_does_not_replay() {
+        let domain = SigningDomain::new("verify_poseidon2_zpass.aleo", "FullCredentials", 1);
+        let (signature, hash) = sign_message_with_logger::<N>(PRIVATE_KEY.to_string(), message(Some(domain.clone())), HashAlgorithm::POSEIDON2, &StdoutLogger).unwrap();
+
+        let (_, plain_hash) = sign_message_with_logger::<N>(PRIVATE_KEY.to_string(), message(None), HashAlgorithm::POSEIDON2, &StdoutLogger).unwrap();
+        assert_eq!(hash, domain.separate::<N>(&plain_hash, HashAlgorithm::POSEIDON2).unwrap());
+
+        let verify = |message| verification::verify_credential::<N>(&message, HashAlgorithm::POSEIDON2, &signature, ISSUER, None).unwrap().status();
+        assert_eq!(verify(message(Some(domain))), VerificationStatus::Valid);
+        assert_eq!(verify(message(None)), VerificationStatus::BadSignature);
+        assert_eq!(verify(message(Some(SigningDomain::new("zpass_hiding.aleo", "FullCredentials", 1)))), VerificationStatus::BadSignature);
+    }
+}
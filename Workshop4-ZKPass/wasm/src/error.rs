@@ -1,7 +1,105 @@
+use core::fmt::Display;
 use thiserror::Error;
+use wasm_bindgen::JsValue;
 
 #[derive(Error, Debug)]
 pub enum CustomError {
     #[error(transparent)]
     Anyhow(#[from] anyhow::Error),
+    #[error("Failed to parse `{path}`: {message}")]
+    Parse { path: String, message: String },
+    #[error("Missing required member `{0}`")]
+    MissingMember(String),
+    #[error("Unexpected member `{0}` is not part of the schema")]
+    UnexpectedMember(String),
+    #[error("Member `{member}` expects a {expected} value, found `{found}`")]
+    MistypedMember { member: String, expected: String, found: String },
+    #[error("Member `{member}` must come before `{after}`")]
+    MisorderedMember { member: String, after: String },
+    #[error("Member `{member}` has unsupported type `{ty}`")]
+    UnsupportedType { member: String, ty: String },
+    #[error("Member `{member}` violates constraint: {reason}")]
+    ConstraintViolation { member: String, reason: String },
+    #[error("Index {index} is out of range for {len} elements")]
+    IndexOutOfRange { index: usize, len: usize },
+    #[error("Invalid key: {0}")]
+    InvalidKey(String),
+    #[error("Hash failure: {0}")]
+    HashFailure(String),
+    #[error("Verification failed: {0}")]
+    VerificationFailed(String),
+    #[error("A credential was already issued under the `issued` key `{0}`")]
+    AlreadyIssued(String),
+}
+
+impl CustomError {
+    /// Creates a parse error for the value at `path`.
+    pub fn parse(path: impl Into<String>, message: impl Display) -> Self {
+        Self::Parse { path: path.into(), message: message.to_string() }
+    }
+
+    /// Returns the stable, machine-readable error code exposed to JavaScript as `error.code`.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::Anyhow(_) => "INTERNAL_ERROR",
+            Self::Parse { .. } => "PARSE_ERROR",
+            Self::MissingMember(_) => "MISSING_MEMBER",
+            Self::UnexpectedMember(_) => "UNEXPECTED_MEMBER",
+            Self::MistypedMember { .. } => "MISTYPED_MEMBER",
+            Self::MisorderedMember { .. } => "MISORDERED_MEMBER",
+            Self::UnsupportedType { .. } => "UNSUPPORTED_TYPE",
+            Self::ConstraintViolation { .. } => "CONSTRAINT_VIOLATION",
+            Self::IndexOutOfRange { .. } => "INDEX_OUT_OF_RANGE",
+            Self::InvalidKey(_) => "INVALID_KEY",
+            Self::HashFailure(_) => "HASH_FAILURE",
+            Self::VerificationFailed(_) => "VERIFICATION_FAILED",
+            Self::AlreadyIssued(_) => "ALREADY_ISSUED",
+        }
+    }
+
+    /// Returns the path of the member or value the error refers to, if any.
+    pub fn path(&self) -> Option<&str> {
+        match self {
+            Self::Parse { path, .. } => Some(path),
+            Self::MissingMember(member) | Self::UnexpectedMember(member) => Some(member),
+            Self::MistypedMember { member, .. }
+            | Self::MisorderedMember { member, .. }
+            | Self::UnsupportedType { member, .. }
+            | Self::ConstraintViolation { member, .. } => Some(member),
+            _ => None,
+        }
+    }
+}
+
+impl From<CustomError> for JsValue {
+    /// Converts the error into a JavaScript `Error` carrying `code` and, when known, `path`.
+    fn from(error: CustomError) -> Self {
+        let js_error = js_sys::Error::new(&error.to_string());
+        js_error.set_name("ZPassError");
+        let _ = js_sys::Reflect::set(&js_error, &JsValue::from_str("code"), &JsValue::from_str(error.code()));
+        if let Some(path) = error.path() {
+            let _ = js_sys::Reflect::set(&js_error, &JsValue::from_str("path"), &JsValue::from_str(path));
+        }
+        js_error.into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_codes_and_paths() {
+        let error = CustomError::parse("block.level", "Failed to parse u16");
+        assert_eq!(error.code(), "PARSE_ERROR");
+        assert_eq!(error.path(), Some("block.level"));
+        assert_eq!(error.to_string(), "Failed to parse `block.level`: Failed to parse u16");
+
+        let error = CustomError::IndexOutOfRange { index: 9, len: 8 };
+        assert_eq!(error.code(), "INDEX_OUT_OF_RANGE");
+        assert_eq!(error.path(), None);
+
+        assert_eq!(CustomError::MissingMember("dob".to_string()).path(), Some("dob"));
+        assert_eq!(CustomError::from(anyhow::anyhow!("boom")).code(), "INTERNAL_ERROR");
+    }
 }
@@ -0,0 +1,388 @@
+use super::*;
+use crate::records::decrypt_record;
+use snarkvm_console::account::{GraphKey, ViewKey};
+use snarkvm_console::program::{Ciphertext, ProgramID, Record};
+use std::collections::HashSet;
+use std::path::Path;
+use zeroize::Zeroize;
+
+/// Which transition outputs a [`RecordFinder`] decrypts.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecordFilter {
+    /// Program ids to scan, e.g. `verify_poseidon2_zpass.aleo`. Empty scans every program.
+    #[serde(default)]
+    pub programs: Vec<String>,
+    /// The record name, checked by recomputing the output commitment.
+    #[serde(default = "default_record_name")]
+    pub record_name: String,
+}
+
+fn default_record_name() -> String {
+    "ZPass".to_string()
+}
+
+impl Default for RecordFilter {
+    fn default() -> Self {
+        Self { programs: Vec::new(), record_name: default_record_name() }
+    }
+}
+
+impl RecordFilter {
+    /// Creates a filter for the `ZPass` records of `programs`.
+    pub fn programs(programs: &[&str]) -> Self {
+        Self { programs: programs.iter().map(|program| program.to_string()).collect(), ..Self::default() }
+    }
+
+    fn matches_program(&self, program: &str) -> bool {
+        self.programs.is_empty() || self.programs.iter().any(|p| p == program)
+    }
+}
+
+/// A record owned by the scanning account.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FoundRecord {
+    pub program: String,
+    pub function: String,
+    pub record_name: String,
+    pub transaction_id: Option<String>,
+    pub transition_id: String,
+    /// Height of the block the record was created in, if the dump holds blocks.
+    pub height: Option<u32>,
+    pub commitment: String,
+    /// The decrypted record with visibility suffixes.
+    pub record: String,
+    /// The serial number, only known when scanning with a private key.
+    pub serial_number: Option<String>,
+    /// The tag published next to the serial number when the record is spent.
+    pub tag: String,
+    pub spent: bool,
+}
+
+/// The result of a scan, which is also the checkpoint a later scan resumes from.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecordScan {
+    /// The highest block height scanned. Blocks at or below it are skipped when resuming.
+    pub height: Option<u32>,
+    pub records: Vec<FoundRecord>,
+}
+
+impl RecordScan {
+    /// Returns the records that have not been spent in any scanned transaction.
+    pub fn unspent(&self) -> impl Iterator<Item = &FoundRecord> {
+        self.records.iter().filter(|record| !record.spent)
+    }
+}
+
+/// Finds the records of an account in local dumps of blocks or transactions, as returned by the
+/// node REST API (`/block/{height}`, `/blocks?start=..&end=..`, `/transaction/{id}`), either as a
+/// JSON document or as NDJSON with one block or transaction per line.
+///
+/// Record outputs are trial-decrypted with the view key. A record is spent once a transition
+/// consumes it: with a private key its serial number is matched against the record inputs,
+/// otherwise its tag, which the view key alone can derive.
+pub struct RecordFinder<N: NetworkNative> {
+    view_key: ViewKey<N>,
+    sk_tag: Field<N>,
+    private_key: Option<PrivateKey<N>>,
+    filter: RecordFilter,
+    scan: RecordScan,
+    /// The height of the scan at the start of the current dump, so unordered blocks are not skipped.
+    checkpoint: Option<u32>,
+    spent: HashSet<String>,
+}
+
+impl<N: NetworkNative> RecordFinder<N> {
+    /// Creates a finder that decrypts with `view_key` and tracks spends by tag.
+    pub fn new(view_key: ViewKey<N>, filter: RecordFilter) -> Result<Self, CustomError> {
+        let sk_tag = GraphKey::try_from(&view_key)
+            .map_err(|e| CustomError::InvalidKey(format!("Failed to derive graph key: {}", e)))?
+            .sk_tag();
+        Ok(Self { view_key, sk_tag, private_key: None, filter, scan: RecordScan::default(), checkpoint: None, spent: HashSet::new() })
+    }
+
+    /// Creates a finder that also computes serial numbers with `private_key`.
+    pub fn from_private_key(private_key: PrivateKey<N>, filter: RecordFilter) -> Result<Self, CustomError> {
+        let view_key = ViewKey::try_from(&private_key)
+            .map_err(|e| CustomError::InvalidKey(format!("Failed to derive view key: {}", e)))?;
+        let mut finder = Self::new(view_key, filter)?;
+        finder.private_key = Some(private_key);
+        Ok(finder)
+    }
+
+    /// Continues from the result of an earlier scan: its records are kept, and may still be
+    /// marked spent, and blocks at or below its height are skipped.
+    pub fn resume(mut self, checkpoint: RecordScan) -> Self {
+        self.checkpoint = checkpoint.height;
+        self.scan = checkpoint;
+        self
+    }
+
+    /// Returns the scan so far.
+    pub fn scan(&self) -> &RecordScan {
+        &self.scan
+    }
+
+    /// Returns the scan, to be stored as the next checkpoint.
+    pub fn into_scan(mut self) -> RecordScan {
+        std::mem::take(&mut self.scan)
+    }
+
+    /// Scans a JSON or NDJSON dump of blocks or transactions.
+    pub fn scan_dump(&mut self, dump: &str) -> Result<(), CustomError> {
+        match serde_json::from_str::<JsonValue>(dump) {
+            Ok(JsonValue::Array(items)) => {
+                for (i, item) in items.iter().enumerate() {
+                    self.scan_item(&format!("dump[{}]", i), item, None)?;
+                }
+            }
+            Ok(item) => self.scan_item("dump", &item, None)?,
+            Err(_) => {
+                for (i, line) in dump.lines().enumerate().filter(|(_, line)| !line.trim().is_empty()) {
+                    let item: JsonValue = serde_json::from_str(line)
+                        .map_err(|e| CustomError::parse(format!("line {}", i + 1), e))?;
+                    self.scan_item(&format!("line {}", i + 1), &item, None)?;
+                }
+            }
+        }
+        self.mark_spent();
+        self.checkpoint = self.scan.height;
+        Ok(())
+    }
+
+    /// Reads and scans a dump file, see [`RecordFinder::scan_dump`].
+    pub fn scan_file(&mut self, path: impl AsRef<Path>) -> Result<(), CustomError> {
+        let dump = std::fs::read_to_string(path.as_ref())
+            .map_err(|e| CustomError::parse(path.as_ref().display().to_string(), e))?;
+        self.scan_dump(&dump)
+    }
+
+    /// Scans a block, a confirmed transaction or a transaction, the latter two at `height` if
+    /// they are part of a block.
+    fn scan_item(&mut self, path: &str, item: &JsonValue, height: Option<u32>) -> Result<(), CustomError> {
+        if let Some(transactions) = item.get("transactions") {
+            let height = item.pointer("/header/metadata/height").and_then(JsonValue::as_u64)
+                .and_then(|height| u32::try_from(height).ok())
+                .ok_or_else(|| CustomError::parse(format!("{}.header.metadata.height", path), "Expected a block height"))?;
+            if self.checkpoint.is_some_and(|checkpoint| height <= checkpoint) {
+                return Ok(());
+            }
+            for (i, confirmed) in transactions.as_array().into_iter().flatten().enumerate() {
+                self.scan_item(&format!("{}.transactions[{}]", path, i), confirmed, Some(height))?;
+            }
+            self.scan.height = self.scan.height.max(Some(height));
+            return Ok(());
+        }
+
+        match item.get("transaction") {
+            Some(transaction) => self.scan_transaction(&format!("{}.transaction", path), transaction, height),
+            None if item.get("execution").is_some() || item.get("fee").is_some() => self.scan_transaction(path, item, height),
+            None => Err(CustomError::parse(path, "Expected a block or a transaction")),
+        }
+    }
+
+    fn scan_transaction(&mut self, path: &str, transaction: &JsonValue, height: Option<u32>) -> Result<(), CustomError> {
+        let id = transaction.get("id").and_then(JsonValue::as_str).map(str::to_string);
+        let executed = transaction.pointer("/execution/transitions").and_then(JsonValue::as_array).into_iter().flatten();
+        let fee = transaction.pointer("/fee/transition").into_iter();
+        for (i, transition) in executed.chain(fee).enumerate() {
+            self.scan_transition(&format!("{}.transitions[{}]", path, i), transition, id.as_deref(), height)?;
+        }
+        Ok(())
+    }
+
+    fn scan_transition(&mut self, path: &str, transition: &JsonValue, transaction_id: Option<&str>, height: Option<u32>) -> Result<(), CustomError> {
+        for input in transition.get("inputs").and_then(JsonValue::as_array).into_iter().flatten() {
+            if input.get("type").and_then(JsonValue::as_str) == Some("record") {
+                for key in ["id", "tag"] {
+                    if let Some(value) = input.get(key).and_then(JsonValue::as_str) {
+                        self.spent.insert(value.to_string());
+                    }
+                }
+            }
+        }
+
+        let program = transition.get("program").and_then(JsonValue::as_str).unwrap_or_default();
+        if !self.filter.matches_program(program) {
+            return Ok(());
+        }
+        for (i, output) in transition.get("outputs").and_then(JsonValue::as_array).into_iter().flatten().enumerate() {
+            if output.get("type").and_then(JsonValue::as_str) != Some("record") {
+                continue;
+            }
+            let path = format!("{}.outputs[{}]", path, i);
+            let field = |key: &str| output.get(key).and_then(JsonValue::as_str)
+                .ok_or_else(|| CustomError::parse(format!("{}.{}", path, key), "Expected a string"));
+            let (commitment, value) = (field("id")?, field("value")?);
+            if self.scan.records.iter().any(|record| record.commitment == commitment) {
+                continue;
+            }
+            let ciphertext = Record::<N, Ciphertext<N>>::from_str(value)
+                .map_err(|e| CustomError::parse(format!("{}.value", path), e))?;
+            if !ciphertext.is_owner(&self.view_key) {
+                continue;
+            }
+            let record = decrypt_record(value, &self.view_key)?;
+
+            let commitment = Field::<N>::from_str(commitment)
+                .map_err(|e| CustomError::parse(format!("{}.id", path), e))?;
+            if self.commitment(&record, program)? != Some(commitment) {
+                continue;
+            }
+            let serial_number = match &self.private_key {
+                Some(private_key) => Some(Record::<N, Plaintext<N>>::serial_number(*private_key, commitment)
+                    .map_err(|e| CustomError::HashFailure(format!("Failed to compute the serial number: {}", e)))?
+                    .to_string()),
+                None => None,
+            };
+            let tag = Record::<N, Plaintext<N>>::tag(self.sk_tag, commitment)
+                .map_err(|e| CustomError::HashFailure(format!("Failed to compute the record tag: {}", e)))?;
+
+            self.scan.records.push(FoundRecord {
+                program: program.to_string(),
+                function: transition.get("function").and_then(JsonValue::as_str).unwrap_or_default().to_string(),
+                record_name: self.filter.record_name.clone(),
+                transaction_id: transaction_id.map(str::to_string),
+                transition_id: transition.get("id").and_then(JsonValue::as_str).unwrap_or_default().to_string(),
+                height,
+                commitment: commitment.to_string(),
+                record: record.to_string(),
+                serial_number,
+                tag: tag.to_string(),
+                spent: false,
+            });
+        }
+        Ok(())
+    }
+
+    /// Returns the commitment of `record` as a `filter.record_name` record of `program`, or
+    /// `None` if the names are not valid identifiers.
+    fn commitment(&self, record: &Record<N, Plaintext<N>>, program: &str) -> Result<Option<Field<N>>, CustomError> {
+        let (Ok(program_id), Ok(record_name)) = (ProgramID::<N>::from_str(program), Identifier::<N>::from_str(&self.filter.record_name)) else {
+            return Ok(None);
+        };
+        record.to_commitment(&program_id, &record_name)
+            .map(Some)
+            .map_err(|e| CustomError::HashFailure(format!("Failed to compute the record commitment: {}", e)))
+    }
+
+    fn mark_spent(&mut self) {
+        for record in self.scan.records.iter_mut() {
+            let by_serial_number = record.serial_number.as_ref().is_some_and(|serial_number| self.spent.contains(serial_number));
+            record.spent |= by_serial_number || self.spent.contains(&record.tag);
+        }
+    }
+}
+
+impl<N: NetworkNative> Drop for RecordFinder<N> {
+    fn drop(&mut self) {
+        if let Some(private_key) = self.private_key.as_mut() {
+            private_key.zeroize();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rng::deterministic_rng;
+    use serde_json::json;
+
+    // Define the network type for the tests
+    type N = TestnetV0;
+
+    const PRIVATE_KEY: &str = "APrivateKey1zkp5LqRmm7535XfiX77VPQEgsS2Dj1B2DvH4QNP1UYrHEoR";
+    const PROGRAM: &str = "verify_poseidon2_zpass.aleo";
+
+    /// A `ZPass` output of `issue` for `private_key`, as `(commitment, ciphertext)`.
+    fn zpass_output(private_key: &PrivateKey<N>, r: u64) -> (Field<N>, String) {
+        let owner = Address::try_from(private_key).unwrap();
+        let randomizer = Scalar::<N>::from_str(&format!("{}scalar", r)).unwrap();
+        let nonce = N::g_scalar_multiply(&randomizer);
+        let record = Record::<N, Plaintext<N>>::from_str(&format!(
+            "{{ owner: {owner}.private, issuer: {owner}.private, dob: 20000101u32.private, nationality: 123field.private, expiry: 20300101u32.private, salt: {r}scalar.private, _nonce: {nonce}.public }}"
+        )).unwrap();
+        let commitment = record.to_commitment(&ProgramID::from_str(PROGRAM).unwrap(), &Identifier::from_str("ZPass").unwrap()).unwrap();
+        (commitment, record.encrypt(randomizer).unwrap().to_string())
+    }
+
+    fn issue(id: &str, outputs: &[(Field<N>, String)]) -> JsonValue {
+        let outputs: Vec<JsonValue> = outputs.iter().map(|(commitment, value)| json!({ "type": "record", "id": commitment.to_string(), "checksum": "0field", "value": value })).collect();
+        json!({ "type": "execute", "id": id, "execution": { "transitions": [{ "id": format!("au1{}", id), "program": PROGRAM, "function": "issue", "inputs": [], "outputs": outputs }] } })
+    }
+
+    fn spend(id: &str, serial_number: Field<N>, tag: Field<N>) -> JsonValue {
+        let input = json!({ "type": "record", "id": serial_number.to_string(), "tag": tag.to_string() });
+        json!({ "type": "execute", "id": id, "execution": { "transitions": [{ "id": format!("au1{}", id), "program": PROGRAM, "function": "more_than_18", "inputs": [input], "outputs": [] }] } })
+    }
+
+    fn block(height: u32, transactions: &[JsonValue]) -> JsonValue {
+        let transactions: Vec<JsonValue> = transactions.iter().enumerate().map(|(i, transaction)| json!({ "status": "accepted", "type": "execute", "index": i, "transaction": transaction })).collect();
+        json!({ "block_hash": format!("ab1{}", height), "header": { "metadata": { "network": 1, "height": height } }, "transactions": transactions })
+    }
+
+    #[test]
+    fn test_find_records_and_spent_status() {
+        let private_key = PrivateKey::<N>::from_str(PRIVATE_KEY).unwrap();
+        let other = PrivateKey::<N>::new(&mut deterministic_rng(1)).unwrap();
+        let (mine, mine_ciphertext) = zpass_output(&private_key, 7);
+        let theirs = zpass_output(&other, 8);
+
+        let view_key = ViewKey::try_from(&private_key).unwrap();
+        let tag = Record::<N, Plaintext<N>>::tag(GraphKey::try_from(&view_key).unwrap().sk_tag(), mine).unwrap();
+        let serial_number = Record::<N, Plaintext<N>>::serial_number(private_key, mine).unwrap();
+        let blocks = [
+            block(10, &[issue("at1a", &[(mine, mine_ciphertext), theirs])]),
+            block(11, &[spend("at1b", serial_number, tag)]),
+        ];
+
+        // Scanning the first block finds the record, still unspent
+        let mut finder = RecordFinder::from_private_key(private_key, RecordFilter::programs(&[PROGRAM])).unwrap();
+        finder.scan_dump(&blocks[0].to_string()).unwrap();
+        let checkpoint = finder.into_scan();
+        assert_eq!(checkpoint.height, Some(10));
+        assert_eq!(checkpoint.records.len(), 1);
+        let found = &checkpoint.records[0];
+        assert_eq!((found.program.as_str(), found.function.as_str(), found.transaction_id.as_deref()), (PROGRAM, "issue", Some("at1a")));
+        assert_eq!(found.height, Some(10));
+        assert_eq!(found.serial_number, Some(serial_number.to_string()));
+        assert!(!found.spent);
+        assert!(ZPassRecord::<N>::decrypt(ZPassProgram::VerifyPoseidon2ZPass, &found.record, &view_key).is_ok());
+
+        // Resuming over both blocks as NDJSON skips block 10 and sees the spend in block 11
+        let ndjson = blocks.iter().map(JsonValue::to_string).collect::<Vec<_>>().join("\n");
+        let checkpoint = serde_json::from_str(&serde_json::to_string(&checkpoint).unwrap()).unwrap();
+        let mut finder = RecordFinder::new(view_key, RecordFilter::programs(&[PROGRAM])).unwrap().resume(checkpoint);
+        finder.scan_dump(&ndjson).unwrap();
+        assert_eq!(finder.scan().height, Some(11));
+        assert_eq!(finder.scan().records.len(), 1);
+        assert!(finder.scan().records[0].spent);
+        assert_eq!(finder.scan().unspent().count(), 0);
+
+        // Blocks may come in any order within a dump
+        let mut finder = RecordFinder::new(view_key, RecordFilter::default()).unwrap();
+        finder.scan_dump(&json!([blocks[1], blocks[0]]).to_string()).unwrap();
+        assert_eq!(finder.scan().height, Some(11));
+        assert!(finder.scan().records[0].spent);
+    }
+
+    #[test]
+    fn test_find_records_filters() {
+        let private_key = PrivateKey::<N>::from_str(PRIVATE_KEY).unwrap();
+        let view_key = ViewKey::try_from(&private_key).unwrap();
+        let transactions = json!([issue("at1a", &[zpass_output(&private_key, 7)])]).to_string();
+
+        let find = |filter: RecordFilter| {
+            let mut finder = RecordFinder::new(view_key, filter).unwrap();
+            finder.scan_dump(&transactions).unwrap();
+            finder.into_scan()
+        };
+        let scan = find(RecordFilter::default());
+        assert_eq!((scan.height, scan.records.len()), (None, 1));
+        assert!(find(RecordFilter::programs(&["zpass_hiding.aleo"])).records.is_empty());
+        assert!(find(RecordFilter { record_name: "Token".to_string(), ..RecordFilter::default() }).records.is_empty());
+
+        let mut finder = RecordFinder::new(view_key, RecordFilter::default()).unwrap();
+        assert_eq!(finder.scan_dump("{ \"height\": 1 }\n").unwrap_err().path(), Some("dump"));
+        assert_eq!(finder.scan_dump("[]\nnot json").unwrap_err().path(), Some("line 1"));
+    }
+}
@@ -3,6 +3,7 @@ use hex::encode;
 use wasm_bindgen::prelude::JsValue;
 use web_sys::console;
 use crate::{Field, NetworkNative};
+use crate::values::{is_canonical, parse_value, ParseMode};
 
 pub trait Logger {
     fn log(&self, message: &str);
@@ -25,112 +26,21 @@ impl Logger for StdoutLogger {
 }
 
 /// Arbitrary message to StructType conversion
-pub fn convert_data_to_struct<N: NetworkNative>(data: JsonValue, logger: &dyn Logger) -> IndexMap<String, Plaintext<N>> {
-    let mut members: IndexMap<String, Plaintext<N>> = IndexMap::new();
+///
+/// Each member is parsed with [`parse_value`], see [`ParseMode`] for how untyped strings are handled.
+///
+/// Without a schema, the members of every object are hashed in sorted key order whatever order
+/// the JSON has.
+pub fn convert_data_to_struct<N: NetworkNative>(data: JsonValue, mode: ParseMode) -> Result<IndexMap<String, Plaintext<N>>, CustomError> {
+    let object = data.as_object()
+        .ok_or_else(|| CustomError::parse("data", "Credential data must be a JSON object"))?;
 
-    for (key, value) in data.as_object().unwrap().clone().into_iter() {
-        match value {
-            JsonValue::String(s) => {
-                let plaintext = match s {
-                    s if s.starts_with("aleo1") => {
-                        let address = Address::<N>::from_str(&s)
-                            .unwrap_or_else(|e| panic!("Failed to parse Aleo address: {}", e));
-                        Plaintext::from(Literal::Address(address))
-                    },
-                    s if s.ends_with("field") => {
-                        let num_str = s.trim_end_matches("field");
-                        let field = string_to_field::<N>(Some(num_str.to_string()))
-                            .unwrap_or_else(|e| panic!("Failed to parse field: {}", e));
-                        Plaintext::from(Literal::Field(field))
-                    },
-                    s if s.ends_with("u8") => {
-                        let num_str = s.trim_end_matches("u8");
-                        let number = num_str.parse::<u8>()
-                            .unwrap_or_else(|e| panic!("Failed to parse u8: {}", e));
-                        Plaintext::from(Literal::U8(U8::<N>::new(number)))
-                    },
-                    s if s.ends_with("u16") => {
-                        let num_str = s.trim_end_matches("u16");
-                        let number = num_str.parse::<u16>()
-                            .unwrap_or_else(|e| panic!("Failed to parse u16: {}", e));
-                        Plaintext::from(Literal::U16(U16::<N>::new(number)))
-                    },
-                    s if s.ends_with("u32") => {
-                        let num_str = s.trim_end_matches("u32");
-                        let number = num_str.parse::<u32>()
-                            .unwrap_or_else(|e| panic!("Failed to parse u32: {}", e));
-                        Plaintext::from(Literal::U32(U32::<N>::new(number)))
-                    },
-                    s if s.ends_with("u64") => {
-                        let num_str = s.trim_end_matches("u64");
-                        let number = num_str.parse::<u64>()
-                            .unwrap_or_else(|e| panic!("Failed to parse u64: {}", e));
-                        Plaintext::from(Literal::U64(U64::<N>::new(number)))
-                    },
-                    s if s.ends_with("u128") => {
-                        let num_str = s.trim_end_matches("u128");
-                        let number = num_str.parse::<u128>()
-                            .unwrap_or_else(|e| panic!("Failed to parse u128: {}", e));
-                        Plaintext::from(Literal::U128(U128::<N>::new(number)))
-                    },
-                    s if s.ends_with("i8") => {
-                        let num_str = s.trim_end_matches("i8");
-                        let number = num_str.parse::<i8>()
-                            .unwrap_or_else(|e| panic!("Failed to parse i8: {}", e));
-                        Plaintext::from(Literal::I8(I8::<N>::new(number)))
-                    },
-                    s if s.ends_with("i16") => {
-                        let num_str = s.trim_end_matches("i16");
-                        let number = num_str.parse::<i16>()
-                            .unwrap_or_else(|e| panic!("Failed to parse i16: {}", e));
-                        Plaintext::from(Literal::I16(I16::<N>::new(number)))
-                    },
-                    s if s.ends_with("i32") => {
-                        let num_str = s.trim_end_matches("i32");
-                        let number = num_str.parse::<i32>()
-                            .unwrap_or_else(|e| panic!("Failed to parse i32: {}", e));
-                        Plaintext::from(Literal::I32(I32::<N>::new(number)))
-                    },
-                    s if s.ends_with("i64") => {
-                        let num_str = s.trim_end_matches("i64");
-                        let number = num_str.parse::<i64>()
-                            .unwrap_or_else(|e| panic!("Failed to parse i64: {}", e));
-                        Plaintext::from(Literal::I64(I64::<N>::new(number)))
-                    },
-                    s if s.ends_with("i128") => {
-                        let num_str = s.trim_end_matches("i128");
-                        let number = num_str.parse::<i128>()
-                            .unwrap_or_else(|e| panic!("Failed to parse i128: {}", e));
-                        Plaintext::from(Literal::I128(I128::<N>::new(number)))
-                    },
-                    s if s == "true" || s == "false" => {
-                        let (_, boolean) = Boolean::<N>::parse(&s)
-                            .unwrap_or_else(|e| panic!("Failed to parse boolean: {}", e));
-                        Plaintext::from(Literal::Boolean(boolean))
-                    },
-                    s if s.ends_with("group") => {
-                        let (_, group) = Group::<N>::parse(&s)
-                            .unwrap_or_else(|e| panic!("Failed to parse group: {}", e));
-                        Plaintext::from(Literal::Group(group))
-                    },
-                    s if s.ends_with("scalar") => {
-                        let (_, scalar) = Scalar::<N>::parse(&s)
-                            .unwrap_or_else(|e| panic!("Failed to parse scalar: {}", e));
-                        Plaintext::from(Literal::Scalar(scalar))
-                    },
-                    s => {
-                        let field = string_to_field(Some(s)).unwrap();
-                        Plaintext::from(Literal::Field(field))
-                    }
-                };
-                members.insert(key, plaintext);
-            },
-            _ => {
-                logger.log(&format!("Unsupported data type: {:?}", value));
-            }
-        }
+    let mut members: IndexMap<String, Plaintext<N>> = IndexMap::new();
+    for (key, value) in object {
+        members.insert(key.clone(), parse_value(key, value, mode)?);
     }
-    members
+    members.sort_keys();
+    Ok(members)
 }
 
 pub fn string_to_field<N: NetworkNative>(input_str: Option<String>) -> Result<Field<N>, anyhow::Error> {
@@ -139,6 +49,17 @@ pub fn string_to_field<N: NetworkNative>(input_str: Option<String>) -> Result<Fi
         return Ok(Field::<N>::zero());
     };
     let string_value = input_str.ok_or(anyhow!("The input string was None"))?;
+
+    // Decimal numbers cover the whole field, not just u128
+    if !string_value.is_empty() && string_value.bytes().all(|byte| byte.is_ascii_digit()) {
+        let literal = format!("{}field", string_value);
+        let field = Field::<N>::from_str(&literal)?;
+        if !is_canonical(&literal, &Literal::Field(field)) {
+            return Err(anyhow!("`{}` is outside the field modulus", string_value));
+        }
+        return Ok(field);
+    }
+
     let u128type = match string_value.as_str().parse::<u128>() {
         Ok(value) => value,
         Err(_) => {
@@ -154,14 +75,14 @@ pub fn string_to_field<N: NetworkNative>(input_str: Option<String>) -> Result<Fi
 }
 
 // Helper functions for various cryptographic and utility operations.
-pub(crate) fn insert_to_map<N: NetworkNative>(map: &mut IndexMap<Identifier<N>, Plaintext<N>>, key: &str, value: Plaintext<N>) -> Result<(), anyhow::Error> {
+pub(crate) fn insert_to_map<N: NetworkNative>(map: &mut IndexMap<Identifier<N>, Plaintext<N>>, key: &str, value: Plaintext<N>) -> Result<(), CustomError> {
     let id = Identifier::from_str(key)
-        .map_err(|e| anyhow!("{}: {}", format!("Can't convert {} to Identifier", key), e))?;
+        .map_err(|e| CustomError::parse(key, format!("Can't convert {} to Identifier: {}", key, e)))?;
     map.insert(id, value);
     Ok(())
 }
 
-pub(crate) fn generate_message_with_addresses_and_fields<N: NetworkNative>(payload: Credential<N>) -> Result<Value<N>, anyhow::Error> {
+pub(crate) fn generate_message_with_addresses_and_fields<N: NetworkNative>(payload: Credential<N>) -> Result<Value<N>, CustomError> {
     // Initialize map with capacity matching payload data size
     let mut map = IndexMap::with_capacity(payload.data.len());
 
@@ -172,44 +93,44 @@ pub(crate) fn generate_message_with_addresses_and_fields<N: NetworkNative>(paylo
     Ok(Value::Plaintext(Plaintext::Struct(map, Default::default())))
 }
 
-pub(crate) fn create_hash<N: NetworkNative>(value: Value<N>, algorithm: HashAlgorithm) -> Result<String, anyhow::Error> {
+pub(crate) fn create_hash<N: NetworkNative>(value: Value<N>, algorithm: HashAlgorithm) -> Result<String, CustomError> {
     let hash = match algorithm  {
         HashAlgorithm::POSEIDON2 => {
             let message = value.to_fields()
-                .map_err(|e| anyhow!("Failed value to Fields conversion: {}", e))?;
+                .map_err(|e| CustomError::HashFailure(format!("Failed value to Fields conversion: {}", e)))?;
             let hash = N::hash_psd2(message.as_slice())
-                .map_err(|e| anyhow!("Failed hash_psd2 conversion: {}", e))?;
+                .map_err(|e| CustomError::HashFailure(format!("Failed hash_psd2 conversion: {}", e)))?;
             hash.to_string()
         }
         HashAlgorithm::BHP1024 => {
             let message = value.to_bits_le();
             let hash = N::hash_bhp1024(message.as_slice())
-                .map_err(|e| anyhow!("Failed hash_bhp1024 conversion: {}", e))?;
+                .map_err(|e| CustomError::HashFailure(format!("Failed hash_bhp1024 conversion: {}", e)))?;
             hash.to_string()
         }
         HashAlgorithm::SHA3_256 => {
             let message = value.to_bits_le();
             let sha_bit_vec = N::hash_sha3_256(message.as_slice())
-                .map_err(|e| anyhow!("Failed hash_sha3_256 conversion: {}", e))?;
+                .map_err(|e| CustomError::HashFailure(format!("Failed hash_sha3_256 conversion: {}", e)))?;
             let bhp_group = N::hash_to_group_bhp256(sha_bit_vec.as_slice())
-                .map_err(|e| anyhow!("Failed hash_to_group_bhp256 conversion: {}", e))?;
+                .map_err(|e| CustomError::HashFailure(format!("Failed hash_to_group_bhp256 conversion: {}", e)))?;
             let literal_group_from_bhp = Literal::Group(bhp_group);
             let casted_to_field = literal_group_from_bhp
                 .cast_lossy(snarkvm_console::program::LiteralType::Field)
-                .map_err(|e| anyhow!("Failed cast_lossy conversion: {}", e))?;
+                .map_err(|e| CustomError::HashFailure(format!("Failed cast_lossy conversion: {}", e)))?;
 
             casted_to_field.to_string()
         }
         HashAlgorithm::KECCAK256 => {
             let message = value.to_bits_le();
             let keccak_bit_vec = N::hash_keccak256(message.as_slice())
-                .map_err(|e| anyhow!("Failed hash_keccak256 conversion: {}", e))?;
+                .map_err(|e| CustomError::HashFailure(format!("Failed hash_keccak256 conversion: {}", e)))?;
             let bhp_group = N::hash_to_group_bhp256(keccak_bit_vec.as_slice())
-                .map_err(|e| anyhow!("Failed hash_to_group_bhp256 conversion: {}", e))?;
+                .map_err(|e| CustomError::HashFailure(format!("Failed hash_to_group_bhp256 conversion: {}", e)))?;
             let literal_group_from_bhp = Literal::Group(bhp_group);
             let casted_to_field = literal_group_from_bhp
                 .cast_lossy(snarkvm_console::program::LiteralType::Field)
-                .map_err(|e| anyhow!("Failed cast_lossy conversion: {}", e))?;
+                .map_err(|e| CustomError::HashFailure(format!("Failed cast_lossy conversion: {}", e)))?;
 
             casted_to_field.to_string()
         }
@@ -217,18 +138,36 @@ pub(crate) fn create_hash<N: NetworkNative>(value: Value<N>, algorithm: HashAlgo
     Ok(hash)
 }
 
-pub(crate) fn sign_message_with_private_key<N: NetworkNative>(
+/// Parses an `APrivateKey1...` string, reporting any failure as [`CustomError::InvalidKey`].
+pub(crate) fn parse_private_key<N: NetworkNative>(private_key: &str) -> Result<PrivateKey<N>, CustomError> {
+    if !private_key.starts_with("APrivateKey1") {
+        return Err(CustomError::InvalidKey("Private key must start with APrivateKey1".to_string()));
+    }
+    PrivateKey::<N>::from_str(private_key)
+        .map_err(|e| CustomError::InvalidKey(format!("Failed to parse private key: {}", e)))
+}
+
+/// Computes Leo's `Poseidon2::hash_to_field(value)`, which hashes the tagged field encoding of
+/// the plaintext rather than its bare fields.
+pub(crate) fn hash_to_field_psd2<N: NetworkNative>(value: Plaintext<N>) -> Result<Field<N>, CustomError> {
+    let fields = Value::Plaintext(value).to_fields()
+        .map_err(|e| CustomError::HashFailure(format!("Failed value to Fields conversion: {}", e)))?;
+    N::hash_psd2(fields.as_slice())
+        .map_err(|e| CustomError::HashFailure(format!("Failed hash_psd2 conversion: {}", e)))
+}
+
+/// Returns the Leo `[field; K]` array of `fields`.
+pub(crate) fn field_array<N: NetworkNative>(fields: &[Field<N>]) -> Plaintext<N> {
+    Plaintext::Array(fields.iter().map(|field| Plaintext::from(Literal::Field(*field))).collect(), Default::default())
+}
+
+pub(crate) fn sign_message_with_private_key<N: NetworkNative, R: SigningRng>(
     private_key: &PrivateKey<N>,
     message: &[Field<N>],
-    rng: &mut TestRng
-) -> Result<(Signature<N>, Scalar<N>), anyhow::Error> {
-    match Signature::<N>::sign(private_key, message, rng) {
-        Ok(signature) => {
-            let nonce = Scalar::rand(rng);
-            Ok((signature, nonce))
-        }
-        Err(_) => Err(anyhow::anyhow!("Failed to create signature")),
-    }
+    rng: &mut R
+) -> Result<Signature<N>, anyhow::Error> {
+    Signature::<N>::sign(private_key, message, rng)
+        .map_err(|_| anyhow::anyhow!("Failed to create signature"))
 }
 
 
@@ -240,13 +179,13 @@ pub(crate) fn verify_signature_with_address_and_message<N: NetworkNative>(
     signature.verify(address, message)
 }
 
-pub(crate) fn string_to_value<N: NetworkNative>(s: &str) -> Value<N> {
-    Value::<N>::from_str(s).expect("Can't convert string to Value")
+pub(crate) fn string_to_value<N: NetworkNative>(s: &str) -> Result<Value<N>, CustomError> {
+    Value::<N>::from_str(s).map_err(|e| CustomError::parse(s, e))
 }
 
-pub(crate) fn string_to_value_fields<N: NetworkNative>(s: &str) -> Vec<Field<N>> {
-    let value  = string_to_value(s);
-    value.to_fields().expect("Can't convert value to fields")
+pub(crate) fn string_to_value_fields<N: NetworkNative>(s: &str) -> Result<Vec<Field<N>>, CustomError> {
+    let value = string_to_value(s)?;
+    value.to_fields().map_err(|e| CustomError::parse(s, format!("Can't convert value to fields: {}", e)))
 }
 
 
@@ -257,6 +196,40 @@ mod tests {
     // Define the network type for the tests
     type N = TestnetV0;
 
+    #[test]
+    fn test_convert_data_to_struct_with_nested_values() {
+        let data = serde_json::json!({
+            "issuer": "aleo1ekyuzclmcw3aj7qncsxxaapxem82mgrd8zadgrrvl5k705zx6q9s7usuqy",
+            "dob": { "type": "u32", "value": 20000101 },
+            "verified": true,
+            "limits": [{ "type": "u8", "value": 1 }, "2u8"],
+            "block": { "level": "3u16", "height": "7u32" }
+        });
+        let members = convert_data_to_struct::<N>(data, ParseMode::Lenient).unwrap();
+        assert_eq!(members.keys().collect::<Vec<_>>(), ["block", "dob", "issuer", "limits", "verified"]);
+        assert_eq!(members["dob"].to_string(), "20000101u32");
+        assert_eq!(members["verified"].to_string(), "true");
+        assert!(matches!(&members["limits"], Plaintext::Array(elements, _) if elements.len() == 2));
+        assert_eq!(members["block"].to_string(), "{\n  height: 7u32,\n  level: 3u16\n}");
+    }
+
+    #[test]
+    fn test_convert_data_to_struct_rejects_untyped_numbers() {
+        assert!(convert_data_to_struct::<N>(serde_json::json!({ "dob": 20000101 }), ParseMode::Lenient).is_err());
+        assert!(convert_data_to_struct::<N>(serde_json::json!({ "dob": null }), ParseMode::Lenient).is_err());
+        assert!(convert_data_to_struct::<N>(serde_json::json!({ "dob": { "type": "u8", "value": 300 } }), ParseMode::Lenient).is_err());
+    }
+
+    #[test]
+    fn test_convert_data_to_struct_reports_parse_errors() {
+        let result = convert_data_to_struct::<N>(serde_json::json!({ "block": { "level": "70000u16" } }), ParseMode::Lenient);
+        assert!(matches!(result, Err(CustomError::Parse { path, .. }) if path == "block.level"));
+
+        let result = convert_data_to_struct::<N>(serde_json::json!({ "issuer": "aleo1invalid" }), ParseMode::Lenient);
+        assert_eq!(result.unwrap_err().code(), "PARSE_ERROR");
+        assert!(string_to_value_fields::<N>("not a value").is_err());
+    }
+
     #[test]
     fn test_string_to_field_with_valid_u128() {
         let input_str = Some("12345".to_string());
@@ -274,4 +247,11 @@ mod tests {
         let field = result.unwrap();
         assert_eq!(field.to_string(), "4714535926995575150field");
     }
+
+    #[test]
+    fn test_string_to_field_with_full_range_number() {
+        let max = "8444461749428370424248824938781546531375899335154063827935233455917409239040";
+        assert_eq!(string_to_field::<N>(Some(max.to_string())).unwrap().to_string(), format!("{}field", max));
+        assert!(string_to_field::<N>(Some("8444461749428370424248824938781546531375899335154063827935233455917409239041".to_string())).is_err());
+    }
 }
@@ -0,0 +1,205 @@
+use super::*;
+use crate::commitments::commit_to_group;
+use snarkvm_console::program::{LiteralType, Record};
+
+/// Returns the schema called `name` of `program`, which must store the record attributes as
+/// group commitments, see [`ZPassProgram::is_hiding`].
+fn hiding_schema(program: ZPassProgram, name: &str) -> Result<CredentialSchema, CustomError> {
+    match program.is_hiding() {
+        true => program.schema(name),
+        false => Err(CustomError::parse("program", format!("{} does not store commitments", program.program_id()))),
+    }
+}
+
+/// Computes the commitment of a single attribute, `BHP256::commit_to_group(value, salt)`.
+pub fn commit_attribute<N: NetworkNative>(value: &Literal<N>, salt: &Scalar<N>) -> Result<Group<N>, CustomError> {
+    commit_to_group(&Plaintext::from(value.clone()), salt)
+}
+
+/// Lays plain credential members out as `layout`, committing every member that `layout` types as
+/// `group` but that is not a group yet. An `owner` member is taken from `subject`.
+pub(crate) fn hide_members<N: NetworkNative>(plain: &IndexMap<String, Plaintext<N>>, layout: &CredentialSchema, salt: &Scalar<N>) -> Result<IndexMap<String, Plaintext<N>>, CustomError> {
+    let mut members = IndexMap::with_capacity(layout.members().len());
+    for member in layout.members() {
+        let source = match member.name.as_str() {
+            "owner" => "subject",
+            name => name,
+        };
+        let value = plain.get(source)
+            .ok_or_else(|| CustomError::MissingMember(source.to_string()))?;
+        let is_hidden = member.member_type.literal_type() == Some(LiteralType::Group)
+            && !matches!(value, Plaintext::Literal(Literal::Group(_), _));
+        let value = match is_hidden {
+            true => Plaintext::from(Literal::Group(commit_to_group(value, salt)?)),
+            false => value.clone(),
+        };
+        members.insert(member.name.clone(), value);
+    }
+    Ok(members)
+}
+
+/// Returns the `salt` scalar of encoded credential or record members.
+pub(crate) fn salt_member<N: NetworkNative>(members: &IndexMap<String, Plaintext<N>>) -> Result<Scalar<N>, CustomError> {
+    match members.get("salt") {
+        Some(Plaintext::Literal(Literal::Scalar(salt), _)) => Ok(*salt),
+        Some(other) => Err(CustomError::MistypedMember { member: "salt".to_string(), expected: "scalar".to_string(), found: other.to_string() }),
+        None => Err(CustomError::MissingMember("salt".to_string())),
+    }
+}
+
+/// Computes the members of the hiding `ZPass` record that `issue` creates for `credentials`,
+/// the plain `FullCredentials` members including `salt`.
+pub fn hide_credentials<N: NetworkNative>(program: ZPassProgram, credentials: &JsonValue) -> Result<IndexMap<String, Plaintext<N>>, CustomError> {
+    let plain = hiding_schema(program, "FullCredentials")?.encode::<N>(credentials)?;
+    let salt = salt_member(&plain)?;
+    hide_members(&plain, &hiding_schema(program, "ZPass")?, &salt)
+}
+
+/// Parses a decrypted record plaintext into its members, `owner` first, without visibilities.
+pub fn record_members<N: NetworkNative>(record: &str) -> Result<IndexMap<String, Plaintext<N>>, CustomError> {
+    let record = Record::<N, Plaintext<N>>::from_str(record)
+        .map_err(|e| CustomError::parse("record", e))?;
+    Ok(records::record_members(&record))
+}
+
+/// Checks that every plain attribute in `openings` (e.g. `{ "dob": "20000101u32" }`) opens the
+/// matching commitment of a decrypted hiding `ZPass` record, using the record salt.
+///
+/// A wrong opening is a `CONSTRAINT_VIOLATION` on that member; the transition would fail with it.
+pub fn check_openings<N: NetworkNative>(program: ZPassProgram, record: &str, openings: &JsonValue) -> Result<(), CustomError> {
+    let members = record_members::<N>(record)?;
+    check_record_openings(program, &members, openings)
+}
+
+fn check_record_openings<N: NetworkNative>(program: ZPassProgram, members: &IndexMap<String, Plaintext<N>>, openings: &JsonValue) -> Result<(), CustomError> {
+    let openings = openings.as_object()
+        .ok_or_else(|| CustomError::parse("openings", "Openings must be a JSON object"))?;
+    let credentials = hiding_schema(program, "FullCredentials")?;
+    let salt = salt_member(members)?;
+
+    for (name, value) in openings {
+        let commitment = match members.get(name) {
+            Some(Plaintext::Literal(Literal::Group(commitment), _)) => commitment,
+            Some(_) => return Err(CustomError::UnsupportedType { member: name.clone(), ty: "a member that is not a commitment".to_string() }),
+            None => return Err(CustomError::UnexpectedMember(name.clone())),
+        };
+        let literal_type = credentials.members().iter()
+            .find(|member| &member.name == name)
+            .and_then(|member| member.member_type.literal_type())
+            .ok_or_else(|| CustomError::UnexpectedMember(name.clone()))?;
+        let opening = schema::parse_json_literal::<N>(value, literal_type)
+            .ok_or_else(|| CustomError::MistypedMember { member: name.clone(), expected: literal_type.to_string(), found: value.to_string() })?;
+
+        if &commit_attribute(&opening, &salt)? != commitment {
+            return Err(CustomError::ConstraintViolation {
+                member: name.clone(),
+                reason: format!("`{}` does not open the record commitment", opening),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Prepares the inputs of `more_than_18(dob, dob_check, zpass)` from a decrypted hiding record.
+///
+/// The `dob` opening is checked against the record and the program's `dob > dob_check` assertion
+/// is evaluated locally, so a call that would fail is rejected before any proof is generated.
+pub fn more_than_18_inputs<N: NetworkNative>(program: ZPassProgram, record: &str, dob: &JsonValue, dob_check: &JsonValue) -> Result<TransitionInputs, CustomError> {
+    let members = record_members::<N>(record)?;
+    check_record_openings(program, &members, &serde_json::json!({ "dob": dob }))?;
+
+    let parse_u32 = |name: &str, value: &JsonValue| match schema::parse_json_literal::<N>(value, LiteralType::U32) {
+        Some(Literal::U32(value)) => Ok(*value),
+        _ => Err(CustomError::MistypedMember { member: name.to_string(), expected: "u32".to_string(), found: value.to_string() }),
+    };
+    let (dob, dob_check) = (parse_u32("dob", dob)?, parse_u32("dob_check", dob_check)?);
+    if dob <= dob_check {
+        return Err(CustomError::ConstraintViolation {
+            member: "dob".to_string(),
+            reason: format!("{}u32 is not greater than dob_check {}u32", dob, dob_check),
+        });
+    }
+
+    let input = |name: &str, value: String| TransitionInput { name: name.to_string(), value, visibility: InputVisibility::Private };
+    Ok(TransitionInputs {
+        program: program.program_id().to_string(),
+        function: "more_than_18".to_string(),
+        inputs: vec![
+            input("dob", format!("{}u32", dob)),
+            input("dob_check", format!("{}u32", dob_check)),
+            input("zpass", Record::<N, Plaintext<N>>::from_str(record).map_err(|e| CustomError::parse("record", e))?.to_string()),
+        ],
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    // Define the network type for the tests
+    type N = TestnetV0;
+
+    const ISSUER: &str = "aleo1ekyuzclmcw3aj7qncsxxaapxem82mgrd8zadgrrvl5k705zx6q9s7usuqy";
+    const SUBJECT: &str = "aleo14w44zfrehup9g894j7tgeyz5gsjuxn0nfn09vd2fvpznrg85rs8skywkte";
+
+    fn credentials() -> JsonValue {
+        json!({
+            "issuer": ISSUER,
+            "subject": SUBJECT,
+            "dob": "20000101u32",
+            "nationality": "123field",
+            "expiry": "20300101u32",
+            "salt": "42scalar"
+        })
+    }
+
+    /// The decrypted record `zpass_hiding.aleo/issue` creates for the test credential.
+    fn record() -> String {
+        let members = hide_credentials::<N>(ZPassProgram::ZPassHiding, &credentials()).unwrap();
+        let entries: Vec<String> = members.iter().map(|(name, value)| format!("{}: {}.private", name, value)).collect();
+        format!("{{ {}, _nonce: 0group.public }}", entries.join(", "))
+    }
+
+    #[test]
+    fn test_hide_credentials_commits_attributes() {
+        let members = hide_credentials::<N>(ZPassProgram::ZPassHiding, &credentials()).unwrap();
+        let names: Vec<&str> = members.keys().map(|name| name.as_str()).collect();
+        assert_eq!(names, ["owner", "issuer", "dob", "nationality", "expiry", "salt"]);
+        assert_eq!(members["owner"].to_string(), SUBJECT);
+
+        let salt = Scalar::<N>::from_str("42scalar").unwrap();
+        let dob = commit_attribute::<N>(&Literal::from_str("20000101u32").unwrap(), &salt).unwrap();
+        assert_eq!(members["dob"], Plaintext::from(Literal::Group(dob)));
+        let issuer = commit_attribute::<N>(&Literal::from_str(ISSUER).unwrap(), &salt).unwrap();
+        assert_eq!(members["issuer"], Plaintext::from(Literal::Group(issuer)));
+    }
+
+    #[test]
+    fn test_check_openings() {
+        let record = record();
+        assert_eq!(record_members::<N>(&record).unwrap(), hide_credentials::<N>(ZPassProgram::ZPassHiding, &credentials()).unwrap());
+
+        let openings = json!({ "issuer": ISSUER, "dob": "20000101u32", "nationality": "123field", "expiry": { "type": "u32", "value": 20300101 } });
+        assert!(check_openings::<N>(ZPassProgram::ZPassHiding, &record, &openings).is_ok());
+
+        let wrong = check_openings::<N>(ZPassProgram::ZPassHiding, &record, &json!({ "dob": "19990101u32" })).unwrap_err();
+        assert_eq!((wrong.code(), wrong.path()), ("CONSTRAINT_VIOLATION", Some("dob")));
+        assert!(matches!(check_openings::<N>(ZPassProgram::ZPassHiding, &record, &json!({ "salt": "42scalar" })), Err(CustomError::UnsupportedType { .. })));
+        assert!(matches!(check_openings::<N>(ZPassProgram::ZPassHiding, &record, &json!({ "name": "Jane" })), Err(CustomError::UnexpectedMember(_))));
+        assert_eq!(check_openings::<N>(ZPassProgram::ZPassInvalidate, &record, &openings).unwrap_err().path(), Some("program"));
+    }
+
+    #[test]
+    fn test_more_than_18_inputs() {
+        let record = record();
+        let inputs = more_than_18_inputs::<N>(ZPassProgram::ZPassHiding, &record, &json!("20000101u32"), &json!({ "type": "u32", "value": 19990101 })).unwrap();
+        assert_eq!(inputs.program, "zpass_hiding.aleo");
+        assert_eq!(inputs.values()[..2], ["20000101u32".to_string(), "19990101u32".to_string()]);
+        assert_eq!(Record::<N, Plaintext<N>>::from_str(&inputs.values()[2]).unwrap(), Record::from_str(&record).unwrap());
+
+        // A failing `dob > dob_check` or a wrong opening would fail the transition
+        assert_eq!(more_than_18_inputs::<N>(ZPassProgram::ZPassHiding, &record, &json!("20000101u32"), &json!("20060101u32")).unwrap_err().path(), Some("dob"));
+        assert!(more_than_18_inputs::<N>(ZPassProgram::ZPassHiding, &record, &json!("19990101u32"), &json!("19980101u32")).is_err());
+    }
+}
@@ -0,0 +1,219 @@
+use super::*;
+use crate::commitments::{ensure_not_issued, issuance_commitment};
+use ring::hmac;
+
+/// Domain prefix mixed into every derived salt so the issuer secret can be shared with other HMAC uses.
+const SALT_DERIVATION_DOMAIN: &[u8] = b"zpass-salt-v1";
+
+/// How the `salt` member of an issued credential is chosen.
+#[derive(Debug, Clone, Copy)]
+pub enum SaltSource<'a> {
+    /// A fresh random scalar sampled from the signing generator.
+    Random,
+    /// HMAC-SHA512 over the subject address and `context`, keyed with an issuer-held secret.
+    /// Re-issuing the same credential to the same subject yields the same salt.
+    Derived { secret: &'a [u8], context: &'a str },
+}
+
+/// A signed, salted credential ready to be passed to an `issue` transition.
+#[wasm_bindgen]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IssuedCredential {
+    pub(crate) signature: String,
+    pub(crate) hash: String,
+    pub(crate) salt: String,
+    pub(crate) public_credentials: String,
+    pub(crate) issued_key: Option<String>,
+}
+
+impl IssuedCredential {
+    pub fn signature(&self) -> &str {
+        &self.signature
+    }
+
+    pub fn hash(&self) -> &str {
+        &self.hash
+    }
+
+    pub fn salt(&self) -> &str {
+        &self.salt
+    }
+
+    /// The `PublicCredentials { salt }` struct literal, e.g. `{ salt: 42scalar }`.
+    pub fn public_credentials(&self) -> &str {
+        &self.public_credentials
+    }
+
+    /// The key `issue_finalize` sets in the `issued` mapping, if the data names a `subject`.
+    pub fn issued_key(&self) -> Option<&str> {
+        self.issued_key.as_deref()
+    }
+}
+
+/// Samples a fresh scalar salt.
+pub fn generate_salt<N: NetworkNative, R: SigningRng>(rng: &mut R) -> Scalar<N> {
+    Scalar::rand(rng)
+}
+
+/// Derives a salt from an issuer secret, the subject address and a free-form context such as a credential id.
+pub fn derive_salt<N: NetworkNative>(secret: &[u8], subject: &str, context: &str) -> Result<Scalar<N>, CustomError> {
+    use snarkvm_console::prelude::*;
+
+    if secret.is_empty() {
+        return Err(CustomError::InvalidKey("The salt derivation secret must not be empty".to_string()));
+    }
+    let subject = Address::<N>::from_str(subject)
+        .map_err(|e| CustomError::parse("subject", e))?;
+
+    let key = hmac::Key::new(hmac::HMAC_SHA512, secret);
+    let mut context_bytes = Vec::with_capacity(SALT_DERIVATION_DOMAIN.len() + 128);
+    context_bytes.extend_from_slice(SALT_DERIVATION_DOMAIN);
+    context_bytes.push(0);
+    context_bytes.extend_from_slice(subject.to_string().as_bytes());
+    context_bytes.push(0);
+    context_bytes.extend_from_slice(context.as_bytes());
+    let tag = hmac::sign(&key, &context_bytes);
+
+    // 512 bits reduced modulo the ~251-bit scalar order leaves a negligible bias
+    Ok(Scalar::new(N::Scalar::from_bytes_le_mod_order(tag.as_ref())))
+}
+
+/// Adds a salt to the credential data, signs the salted struct and returns everything the
+/// holder needs for `issue(sig, pri, pub)`.
+///
+/// The salt is appended as the last member, matching `FullCredentials` in every ZPass program.
+pub fn issue_credential<N: NetworkNative>(private_key: String, message: SignInboundMessage, hash: HashAlgorithm, salt: SaltSource, logger: &dyn Logger) -> Result<IssuedCredential, CustomError> {
+    issue_credential_with_rng::<N, _>(private_key, message, hash, salt, logger, &mut secure_rng())
+}
+
+/// Issues a credential like [`issue_credential`], sampling the signature nonce and random salts from `rng`.
+pub fn issue_credential_with_rng<N: NetworkNative, R: SigningRng>(private_key: String, message: SignInboundMessage, hash: HashAlgorithm, salt: SaltSource, logger: &dyn Logger, rng: &mut R) -> Result<IssuedCredential, CustomError> {
+    issue_salted_credential::<N, R>(private_key, message, hash, salt, None, logger, rng)
+}
+
+/// Issues a credential like [`issue_credential`], but refuses to sign when its `issued` key is
+/// already in `issued`, a snapshot of the program's `issued` mapping keys.
+///
+/// The `issue` transition would be rejected for such a credential. This mostly matters for
+/// [`SaltSource::Derived`], where re-issuing to the same subject and context yields the same key.
+pub fn issue_credential_checked<N: NetworkNative>(private_key: String, message: SignInboundMessage, hash: HashAlgorithm, salt: SaltSource, issued: &[String], logger: &dyn Logger) -> Result<IssuedCredential, CustomError> {
+    issue_salted_credential::<N, _>(private_key, message, hash, salt, Some(issued), logger, &mut secure_rng())
+}
+
+fn issue_salted_credential<N: NetworkNative, R: SigningRng>(private_key: String, mut message: SignInboundMessage, hash: HashAlgorithm, salt: SaltSource, issued: Option<&[String]>, logger: &dyn Logger, rng: &mut R) -> Result<IssuedCredential, CustomError> {
+    let data = message.data.as_object_mut()
+        .ok_or_else(|| CustomError::parse("data", "Credential data must be a JSON object"))?;
+    if data.contains_key("salt") {
+        return Err(CustomError::UnexpectedMember("salt".to_string()));
+    }
+
+    let salt = match salt {
+        SaltSource::Random => generate_salt::<N, R>(rng),
+        SaltSource::Derived { secret, context } => {
+            let subject = data.get("subject").and_then(JsonValue::as_str)
+                .ok_or_else(|| CustomError::MissingMember("subject".to_string()))?;
+            derive_salt::<N>(secret, subject, context)?
+        }
+    };
+
+    let subject = data.get("subject").and_then(JsonValue::as_str)
+        .map(|subject| Address::<N>::from_str(subject).map_err(|e| CustomError::parse("subject", e)))
+        .transpose()?;
+    let issued_key = subject.map(|subject| issuance_commitment(&subject, &salt)).transpose()?;
+    if let Some(issued) = issued {
+        let key = issued_key.ok_or_else(|| CustomError::MissingMember("subject".to_string()))?;
+        ensure_not_issued(&key, issued)?;
+    }
+    data.insert("salt".to_string(), JsonValue::String(salt.to_string()));
+
+    let (signature, hash) = sign_message_with_rng::<N, R>(private_key, message, hash, logger, rng)?;
+
+    Ok(IssuedCredential {
+        signature,
+        hash,
+        salt: salt.to_string(),
+        public_credentials: format!("{{ salt: {} }}", salt),
+        issued_key: issued_key.map(|key| key.to_string()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    // Define the network type for the tests
+    type N = TestnetV0;
+
+    const PRIVATE_KEY: &str = "APrivateKey1zkp5LqRmm7535XfiX77VPQEgsS2Dj1B2DvH4QNP1UYrHEoR";
+    const ISSUER: &str = "aleo1ekyuzclmcw3aj7qncsxxaapxem82mgrd8zadgrrvl5k705zx6q9s7usuqy";
+    const SUBJECT: &str = "aleo14w44zfrehup9g894j7tgeyz5gsjuxn0nfn09vd2fvpznrg85rs8skywkte";
+
+    fn private_credentials() -> SignInboundMessage {
+        SignInboundMessage::from_json(json!({
+            "issuer": ISSUER,
+            "subject": SUBJECT,
+            "dob": "20000101u32",
+            "nationality": "123field",
+            "expiry": "20300101u32"
+        })).with_credential_schema(CredentialSchema::from_leo(include_str!("../../programs/zpass_hiding/src/main.leo"), "FullCredentials").unwrap())
+    }
+
+    #[test]
+    fn test_derive_salt_is_deterministic() {
+        let first = derive_salt::<N>(b"issuer secret", SUBJECT, "passport").unwrap();
+        let second = derive_salt::<N>(b"issuer secret", SUBJECT, "passport").unwrap();
+        assert_eq!(first, second);
+        assert_ne!(first, derive_salt::<N>(b"issuer secret", SUBJECT, "license").unwrap());
+        assert_ne!(first, derive_salt::<N>(b"other secret", SUBJECT, "passport").unwrap());
+        assert_ne!(first, derive_salt::<N>(b"issuer secret", ISSUER, "passport").unwrap());
+        assert!(derive_salt::<N>(b"", SUBJECT, "passport").is_err());
+    }
+
+    #[test]
+    fn test_issue_credential_signs_salted_struct() {
+        let issued = issue_credential::<N>(PRIVATE_KEY.to_string(), private_credentials(), HashAlgorithm::POSEIDON2, SaltSource::Random, &StdoutLogger).unwrap();
+        assert!(issued.salt().ends_with("scalar"));
+        assert_eq!(issued.public_credentials(), format!("{{ salt: {} }}", issued.salt()));
+
+        // The hash must equal Poseidon2::hash_to_field(FullCredentials) as rebuilt by `issue`
+        let full = Value::<N>::from_str(&format!(
+            "{{ issuer: {ISSUER}, subject: {SUBJECT}, dob: 20000101u32, nationality: 123field, expiry: 20300101u32, salt: {} }}",
+            issued.salt()
+        )).unwrap();
+        assert_eq!(issued.hash(), create_hash(full, HashAlgorithm::POSEIDON2).unwrap());
+
+        let signature = Signature::<N>::from_str(issued.signature()).unwrap();
+        let issuer = Address::<N>::from_str(ISSUER).unwrap();
+        assert!(signature.verify(&issuer, &string_to_value_fields::<N>(issued.hash()).unwrap()));
+    }
+
+    #[test]
+    fn test_issue_credential_with_derived_salt() {
+        let salt = SaltSource::Derived { secret: b"issuer secret", context: "passport" };
+        let first = issue_credential::<N>(PRIVATE_KEY.to_string(), private_credentials(), HashAlgorithm::POSEIDON2, salt, &StdoutLogger).unwrap();
+        let second = issue_credential::<N>(PRIVATE_KEY.to_string(), private_credentials(), HashAlgorithm::POSEIDON2, salt, &StdoutLogger).unwrap();
+        assert_eq!(first.salt(), derive_salt::<N>(b"issuer secret", SUBJECT, "passport").unwrap().to_string());
+        assert_eq!(first.hash(), second.hash());
+
+        let mut salted = private_credentials();
+        salted.data["salt"] = json!("1scalar");
+        assert!(issue_credential::<N>(PRIVATE_KEY.to_string(), salted, HashAlgorithm::POSEIDON2, SaltSource::Random, &StdoutLogger).is_err());
+    }
+
+    #[test]
+    fn test_issue_credential_checked_refuses_issued_keys() {
+        let salt = SaltSource::Derived { secret: b"issuer secret", context: "passport" };
+        let first = issue_credential_checked::<N>(PRIVATE_KEY.to_string(), private_credentials(), HashAlgorithm::POSEIDON2, salt, &[], &StdoutLogger).unwrap();
+        let subject = Address::<N>::from_str(SUBJECT).unwrap();
+        let expected = issuance_commitment::<N>(&subject, &Scalar::from_str(first.salt()).unwrap()).unwrap();
+        assert_eq!(first.issued_key(), Some(expected.to_string().as_str()));
+
+        // The same derived salt would fail `issue_finalize`
+        let issued = vec![first.issued_key().unwrap().to_string()];
+        let result = issue_credential_checked::<N>(PRIVATE_KEY.to_string(), private_credentials(), HashAlgorithm::POSEIDON2, salt, &issued, &StdoutLogger);
+        assert_eq!(result.unwrap_err().code(), "ALREADY_ISSUED");
+        let other = SaltSource::Derived { secret: b"issuer secret", context: "license" };
+        assert!(issue_credential_checked::<N>(PRIVATE_KEY.to_string(), private_credentials(), HashAlgorithm::POSEIDON2, other, &issued, &StdoutLogger).is_ok());
+    }
+}
@@ -0,0 +1,257 @@
+use super::*;
+use crate::merkle_tree::sign_root_with_key;
+use snarkvm_console::account::ViewKey;
+use zeroize::Zeroize;
+
+/// The parsed private key of an [`Issuer`] on its network.
+enum IssuerKey {
+    Testnet(PrivateKey<TestnetV0>),
+    Mainnet(PrivateKey<MainnetV0>),
+}
+
+/// An issuer account built once from a private key and reused for every signature.
+///
+/// The key is parsed a single time, and the copy held by the instance is zeroized when the
+/// issuer is dropped, which is what `free()` does on the JavaScript side. `PrivateKey` is
+/// `Copy`, so signing may leave transient copies on the stack that are not zeroized.
+#[wasm_bindgen]
+pub struct Issuer {
+    key: IssuerKey,
+    address: String,
+}
+
+/// A signed merkle root together with its leaves and one proof per leaf, ready for
+/// `zpass_merkle_8.aleo/issue` and `verify`.
+#[wasm_bindgen]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MerkleCredential {
+    pub(crate) signature: String,
+    pub(crate) root: String,
+    pub(crate) leaves: Vec<String>,
+    pub(crate) proofs: Vec<Vec<String>>,
+}
+
+impl MerkleCredential {
+    pub fn signature(&self) -> &str {
+        &self.signature
+    }
+
+    pub fn root(&self) -> &str {
+        &self.root
+    }
+
+    /// The eight leaf hashes; the first one is the hashed issuer address.
+    pub fn leaves(&self) -> &[String] {
+        &self.leaves
+    }
+
+    /// Returns the `[field; 3]` proof of the leaf at `index`.
+    pub fn proof(&self, index: usize) -> Result<&[String], CustomError> {
+        self.proofs.get(index)
+            .map(Vec::as_slice)
+            .ok_or(CustomError::IndexOutOfRange { index, len: self.proofs.len() })
+    }
+}
+
+impl Issuer {
+    /// Parses `private_key` for `network` and derives the issuer address.
+    pub fn new(private_key: &str, network: Network) -> Result<Self, CustomError> {
+        let key = match network {
+            Network::Testnet => IssuerKey::Testnet(parse_private_key(private_key)?),
+            Network::Mainnet => IssuerKey::Mainnet(parse_private_key(private_key)?),
+        };
+        Self::from_key(key)
+    }
+
+    /// Unlocks a passphrase-encrypted [`Keystore`] for `network`.
+    pub fn from_keystore(keystore: &Keystore, passphrase: &str, network: Network) -> Result<Self, CustomError> {
+        let key = match network {
+            Network::Testnet => IssuerKey::Testnet(keystore.decrypt(passphrase)?),
+            Network::Mainnet => IssuerKey::Mainnet(keystore.decrypt(passphrase)?),
+        };
+        Self::from_key(key)
+    }
+
+    fn from_key(key: IssuerKey) -> Result<Self, CustomError> {
+        let address = match &key {
+            IssuerKey::Testnet(key) => derive_address(key)?,
+            IssuerKey::Mainnet(key) => derive_address(key)?,
+        };
+        Ok(Self { key, address })
+    }
+
+    /// Encrypts the issuer key under `passphrase`, see [`Keystore::encrypt`].
+    pub fn to_keystore(&self, passphrase: &str) -> Result<Keystore, CustomError> {
+        match &self.key {
+            IssuerKey::Testnet(key) => Keystore::encrypt(key, passphrase),
+            IssuerKey::Mainnet(key) => Keystore::encrypt(key, passphrase),
+        }
+    }
+
+    pub fn network(&self) -> Network {
+        match self.key {
+            IssuerKey::Testnet(_) => Network::Testnet,
+            IssuerKey::Mainnet(_) => Network::Mainnet,
+        }
+    }
+
+    pub fn address(&self) -> &str {
+        &self.address
+    }
+
+    /// Returns the view key of the issuer account.
+    pub fn view_key(&self) -> Result<String, CustomError> {
+        let view_key = match &self.key {
+            IssuerKey::Testnet(key) => ViewKey::try_from(key).map(|view_key| view_key.to_string()),
+            IssuerKey::Mainnet(key) => ViewKey::try_from(key).map(|view_key| view_key.to_string()),
+        };
+        view_key.map_err(|e| CustomError::InvalidKey(format!("Failed to derive view key: {}", e)))
+    }
+
+    /// Signs a credential like [`sign_message_with_logger`] and returns `(signature, hash)`.
+    pub fn sign_credential(&self, message: &SignInboundMessage, hash: HashAlgorithm, logger: &dyn Logger) -> Result<(String, String), CustomError> {
+        self.sign_credential_with_rng(message, hash, logger, &mut secure_rng())
+    }
+
+    /// Signs a credential like [`Issuer::sign_credential`], sampling the nonce from `rng`.
+    pub fn sign_credential_with_rng<R: SigningRng>(&self, message: &SignInboundMessage, hash: HashAlgorithm, logger: &dyn Logger, rng: &mut R) -> Result<(String, String), CustomError> {
+        match &self.key {
+            IssuerKey::Testnet(key) => sign_message_with_key(key, message, hash, logger, rng),
+            IssuerKey::Mainnet(key) => sign_message_with_key(key, message, hash, logger, rng),
+        }
+    }
+
+    /// Signs a merkle root like [`sign_root`].
+    pub fn sign_merkle_root(&self, root: &str) -> Result<String, CustomError> {
+        self.sign_merkle_root_with_rng(root, &mut secure_rng())
+    }
+
+    /// Signs a merkle root like [`Issuer::sign_merkle_root`], sampling the nonce from `rng`.
+    pub fn sign_merkle_root_with_rng<R: SigningRng>(&self, root: &str, rng: &mut R) -> Result<String, CustomError> {
+        match &self.key {
+            IssuerKey::Testnet(key) => sign_root_with_key(key, root, rng),
+            IssuerKey::Mainnet(key) => sign_root_with_key(key, root, rng),
+        }
+    }
+
+    /// Hashes up to seven credential values into merkle leaves and signs the root.
+    ///
+    /// The issuer address is prepended as the first leaf, as `zpass_merkle_8.aleo/issue`
+    /// requires. Values are parsed like [`hash_values_size_8`].
+    pub fn build_merkle_credential(&self, values: &[JsonValue], mode: ParseMode) -> Result<MerkleCredential, CustomError> {
+        self.build_merkle_credential_with_rng(values, mode, &mut secure_rng())
+    }
+
+    /// Builds a merkle credential like [`Issuer::build_merkle_credential`], sampling the nonce from `rng`.
+    pub fn build_merkle_credential_with_rng<R: SigningRng>(&self, values: &[JsonValue], mode: ParseMode, rng: &mut R) -> Result<MerkleCredential, CustomError> {
+        let mut leaves = Vec::with_capacity(values.len() + 1);
+        leaves.push(JsonValue::String(self.address.clone()));
+        leaves.extend_from_slice(values);
+        match &self.key {
+            IssuerKey::Testnet(key) => build_merkle_credential_with_key(key, &leaves, mode, rng),
+            IssuerKey::Mainnet(key) => build_merkle_credential_with_key(key, &leaves, mode, rng),
+        }
+    }
+}
+
+impl Drop for Issuer {
+    fn drop(&mut self) {
+        match &mut self.key {
+            IssuerKey::Testnet(key) => key.zeroize(),
+            IssuerKey::Mainnet(key) => key.zeroize(),
+        }
+    }
+}
+
+fn derive_address<N: NetworkNative>(private_key: &PrivateKey<N>) -> Result<String, CustomError> {
+    Address::<N>::try_from(private_key)
+        .map(|address| address.to_string())
+        .map_err(|e| CustomError::InvalidKey(format!("Failed to derive issuer address: {}", e)))
+}
+
+fn build_merkle_credential_with_key<N: NetworkNative, R: SigningRng>(private_key: &PrivateKey<N>, values: &[JsonValue], mode: ParseMode, rng: &mut R) -> Result<MerkleCredential, CustomError> {
+    let leaves = hash_values_size_8::<N>(values, mode)?;
+    let tree = MerkleTree::<N>::new(leaves.clone())?;
+    let root = tree.root().to_string();
+    let signature = sign_root_with_key(private_key, &root, rng)?;
+
+    let proofs = (0..leaves.len())
+        .map(|index| tree.get_proof(index).map(|proof| proof.iter().map(|f| f.to_string()).collect()))
+        .collect::<Result<_, _>>()?;
+    Ok(MerkleCredential {
+        signature,
+        root,
+        leaves: leaves.iter().map(|f| f.to_string()).collect(),
+        proofs,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rng::deterministic_rng;
+    use serde_json::json;
+
+    // Define the network type for the tests
+    type N = TestnetV0;
+
+    const PRIVATE_KEY: &str = "APrivateKey1zkp5LqRmm7535XfiX77VPQEgsS2Dj1B2DvH4QNP1UYrHEoR";
+    const ISSUER: &str = "aleo1ekyuzclmcw3aj7qncsxxaapxem82mgrd8zadgrrvl5k705zx6q9s7usuqy";
+
+    #[test]
+    fn test_issuer_matches_key_based_signing() {
+        let issuer = Issuer::new(PRIVATE_KEY, Network::Testnet).unwrap();
+        assert_eq!(issuer.address(), ISSUER);
+        assert!(issuer.view_key().unwrap().starts_with("AViewKey1"));
+
+        let message = SignInboundMessage::from_json(json!({ "issuer": ISSUER, "dob": "20000101u32" })).with_mode(ParseMode::Strict);
+        let signed = issuer.sign_credential_with_rng(&message, HashAlgorithm::POSEIDON2, &StdoutLogger, &mut deterministic_rng(7)).unwrap();
+        let expected = sign_message_with_rng::<N, _>(PRIVATE_KEY.to_string(), message, HashAlgorithm::POSEIDON2, &StdoutLogger, &mut deterministic_rng(7)).unwrap();
+        assert_eq!(signed, expected);
+
+        let root = MerkleTree::<N>::new(hash_inputs_size_8::<N>(vec!["123field"]).unwrap()).unwrap().root().to_string();
+        let signature = issuer.sign_merkle_root_with_rng(&root, &mut deterministic_rng(7)).unwrap();
+        assert_eq!(signature, sign_root_with_rng::<N, _>(PRIVATE_KEY, &root, &mut deterministic_rng(7)).unwrap());
+    }
+
+    #[test]
+    fn test_build_merkle_credential() {
+        let issuer = Issuer::new(PRIVATE_KEY, Network::Testnet).unwrap();
+        let credential = issuer.build_merkle_credential(&[json!("20000101u32"), json!("123field")], ParseMode::Strict).unwrap();
+
+        // The issuer is the first leaf, as `zpass_merkle_8.aleo/issue` asserts
+        let leaves = hash_values_size_8::<N>(&[json!(ISSUER), json!("20000101u32"), json!("123field")], ParseMode::Strict).unwrap();
+        let tree = MerkleTree::<N>::new(leaves.clone()).unwrap();
+        assert_eq!(credential.leaves()[0], leaves[0].to_string());
+        assert_eq!(credential.root(), tree.root().to_string());
+
+        let proof = credential.proof(2).unwrap().iter().map(|f| Field::<N>::from_str(f).unwrap()).collect();
+        assert!(tree.verify_proof(leaves[2], &proof).unwrap());
+        assert!(matches!(credential.proof(8), Err(CustomError::IndexOutOfRange { .. })));
+
+        let signature = Signature::<N>::from_str(credential.signature()).unwrap();
+        let root = string_to_value_fields::<N>(credential.root()).unwrap();
+        assert!(verify_signature_with_address_and_message(&signature, &Address::from_str(ISSUER).unwrap(), &root));
+
+        // Seven values plus the issuer fill the tree
+        assert!(issuer.build_merkle_credential(&vec![json!("1u8"); 8], ParseMode::Strict).is_err());
+    }
+
+    #[test]
+    fn test_issuer_from_keystore() {
+        let issuer = Issuer::new(PRIVATE_KEY, Network::Testnet).unwrap();
+        let private_key = PrivateKey::<N>::from_str(PRIVATE_KEY).unwrap();
+        let keystore = Keystore::encrypt_with_rng(&private_key, "correct horse", crate::keystore::MIN_ITERATIONS, &mut deterministic_rng(7)).unwrap();
+
+        let unlocked = Issuer::from_keystore(&keystore, "correct horse", Network::Testnet).unwrap();
+        assert_eq!(unlocked.address(), issuer.address());
+        assert_eq!(unlocked.view_key().unwrap(), issuer.view_key().unwrap());
+        assert_eq!(Issuer::from_keystore(&keystore, "wrong horse", Network::Testnet).err().unwrap().code(), "INVALID_KEY");
+    }
+
+    #[test]
+    fn test_issuer_rejects_invalid_key() {
+        assert_eq!(Issuer::new("APrivateKey1invalid", Network::Testnet).err().unwrap().code(), "INVALID_KEY");
+        assert!(matches!(Issuer::new(PRIVATE_KEY, Network::Mainnet).unwrap().network(), Network::Mainnet));
+    }
+}
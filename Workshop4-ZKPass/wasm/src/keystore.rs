@@ -0,0 +1,224 @@
+use super::*;
+use ring::{aead, pbkdf2};
+use std::num::NonZeroU32;
+use zeroize::Zeroize;
+
+/// The envelope version written by [`Keystore::encrypt`].
+pub const KEYSTORE_VERSION: u32 = 1;
+
+/// PBKDF2 iterations used when none are given, following the OWASP recommendation for HMAC-SHA256.
+pub const DEFAULT_ITERATIONS: u32 = 600_000;
+
+/// The fewest PBKDF2 iterations a keystore may be created or unlocked with.
+pub const MIN_ITERATIONS: u32 = 100_000;
+
+const SALT_LEN: usize = 16;
+const KEY_LEN: usize = 32;
+
+/// Domain prefix of the associated data, which also binds the issuer address to the ciphertext.
+const AAD_DOMAIN: &str = "zpass-keystore";
+
+/// A private key encrypted under a passphrase, stored as a versioned JSON envelope:
+///
+/// ```json
+/// {
+///   "version": 1,
+///   "address": "aleo1...",
+///   "kdf": { "name": "pbkdf2-hmac-sha256", "iterations": 600000, "salt": "<hex>" },
+///   "cipher": { "name": "aes-256-gcm", "nonce": "<hex>" },
+///   "ciphertext": "<hex>"
+/// }
+/// ```
+///
+/// The ciphertext holds the 32-byte account seed. The address is public so the issuer can be
+/// identified without the passphrase, and it is authenticated as associated data.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Keystore {
+    pub version: u32,
+    pub address: String,
+    pub kdf: KeystoreKdf,
+    pub cipher: KeystoreCipher,
+    pub ciphertext: String,
+}
+
+/// Key derivation function of a [`Keystore`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "name")]
+pub enum KeystoreKdf {
+    #[serde(rename = "pbkdf2-hmac-sha256")]
+    Pbkdf2HmacSha256 { iterations: u32, salt: String },
+}
+
+/// Authenticated cipher of a [`Keystore`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "name")]
+pub enum KeystoreCipher {
+    #[serde(rename = "aes-256-gcm")]
+    Aes256Gcm { nonce: String },
+}
+
+impl Keystore {
+    /// Encrypts `private_key` under `passphrase` with [`DEFAULT_ITERATIONS`] and a fresh salt and nonce.
+    pub fn encrypt<N: NetworkNative>(private_key: &PrivateKey<N>, passphrase: &str) -> Result<Self, CustomError> {
+        Self::encrypt_with_rng(private_key, passphrase, DEFAULT_ITERATIONS, &mut secure_rng())
+    }
+
+    /// Encrypts like [`Keystore::encrypt`] with `iterations`, sampling the salt and nonce from `rng`.
+    pub fn encrypt_with_rng<N: NetworkNative, R: SigningRng>(private_key: &PrivateKey<N>, passphrase: &str, iterations: u32, rng: &mut R) -> Result<Self, CustomError> {
+        use snarkvm_console::prelude::*;
+
+        check_iterations(iterations)?;
+        let address = Address::<N>::try_from(private_key)
+            .map_err(|e| CustomError::InvalidKey(format!("Failed to derive issuer address: {}", e)))?
+            .to_string();
+
+        let mut salt = [0u8; SALT_LEN];
+        let mut nonce = [0u8; aead::NONCE_LEN];
+        rng.fill_bytes(&mut salt);
+        rng.fill_bytes(&mut nonce);
+
+        let mut in_out = private_key.seed().to_bytes_le()
+            .map_err(|e| CustomError::InvalidKey(format!("Failed to serialize private key: {}", e)))?;
+        let key = cipher_key(passphrase, iterations, &salt)?;
+        let sealed = key.seal_in_place_append_tag(aead::Nonce::assume_unique_for_key(nonce), aad(&address), &mut in_out);
+        if sealed.is_err() {
+            in_out.zeroize();
+            return Err(CustomError::from(anyhow!("Failed to encrypt the private key")));
+        }
+
+        Ok(Self {
+            version: KEYSTORE_VERSION,
+            address,
+            kdf: KeystoreKdf::Pbkdf2HmacSha256 { iterations, salt: hex::encode(salt) },
+            cipher: KeystoreCipher::Aes256Gcm { nonce: hex::encode(nonce) },
+            ciphertext: hex::encode(in_out),
+        })
+    }
+
+    /// Decrypts the private key. A wrong passphrase and a tampered envelope are both `INVALID_KEY`.
+    pub fn decrypt<N: NetworkNative>(&self, passphrase: &str) -> Result<PrivateKey<N>, CustomError> {
+        use snarkvm_console::prelude::*;
+
+        if self.version != KEYSTORE_VERSION {
+            return Err(CustomError::parse("version", format!("Unsupported keystore version {}, expected {}", self.version, KEYSTORE_VERSION)));
+        }
+        let KeystoreKdf::Pbkdf2HmacSha256 { iterations, salt } = &self.kdf;
+        let KeystoreCipher::Aes256Gcm { nonce } = &self.cipher;
+        check_iterations(*iterations)?;
+        let salt = decode_hex("kdf.salt", salt)?;
+        let nonce: [u8; aead::NONCE_LEN] = decode_hex("cipher.nonce", nonce)?.try_into()
+            .map_err(|_| CustomError::parse("cipher.nonce", format!("Expected {} bytes", aead::NONCE_LEN)))?;
+        let mut in_out = decode_hex("ciphertext", &self.ciphertext)?;
+
+        let key = cipher_key(passphrase, *iterations, &salt)?;
+        let seed = match key.open_in_place(aead::Nonce::assume_unique_for_key(nonce), aad(&self.address), &mut in_out) {
+            Ok(plaintext) => Field::<N>::from_bytes_le(plaintext),
+            Err(_) => return Err(CustomError::InvalidKey("Wrong passphrase or corrupted keystore".to_string())),
+        };
+        in_out.zeroize();
+
+        let private_key = seed.and_then(PrivateKey::<N>::try_from)
+            .map_err(|e| CustomError::InvalidKey(format!("Failed to restore private key: {}", e)))?;
+        let address = Address::<N>::try_from(&private_key)
+            .map_err(|e| CustomError::InvalidKey(format!("Failed to derive issuer address: {}", e)))?;
+        if address.to_string() != self.address {
+            return Err(CustomError::InvalidKey("The keystore does not belong to its address".to_string()));
+        }
+        Ok(private_key)
+    }
+
+    /// Parses a keystore from its JSON envelope.
+    pub fn from_json(json: &str) -> Result<Self, CustomError> {
+        serde_json::from_str(json).map_err(|e| CustomError::parse("keystore", e))
+    }
+
+    /// Serializes the keystore into its JSON envelope.
+    pub fn to_json(&self) -> Result<String, CustomError> {
+        serde_json::to_string(self).map_err(|e| CustomError::from(anyhow!("Failed to serialize keystore: {}", e)))
+    }
+}
+
+/// Encrypts an `APrivateKey1...` string into a keystore, see [`Keystore::encrypt`].
+pub fn encrypt_private_key<N: NetworkNative>(private_key: &str, passphrase: &str) -> Result<Keystore, CustomError> {
+    let private_key = parse_private_key::<N>(private_key)?;
+    Keystore::encrypt(&private_key, passphrase)
+}
+
+fn check_iterations(iterations: u32) -> Result<(), CustomError> {
+    if iterations < MIN_ITERATIONS {
+        return Err(CustomError::ConstraintViolation {
+            member: "kdf.iterations".to_string(),
+            reason: format!("{} iterations is below the minimum of {}", iterations, MIN_ITERATIONS),
+        });
+    }
+    Ok(())
+}
+
+fn cipher_key(passphrase: &str, iterations: u32, salt: &[u8]) -> Result<aead::LessSafeKey, CustomError> {
+    let iterations = NonZeroU32::new(iterations)
+        .ok_or_else(|| CustomError::parse("kdf.iterations", "Iterations must not be zero"))?;
+    let mut key = [0u8; KEY_LEN];
+    pbkdf2::derive(pbkdf2::PBKDF2_HMAC_SHA256, iterations, salt, passphrase.as_bytes(), &mut key);
+    let unbound = aead::UnboundKey::new(&aead::AES_256_GCM, &key);
+    key.zeroize();
+    unbound
+        .map(aead::LessSafeKey::new)
+        .map_err(|_| CustomError::from(anyhow!("Failed to create the keystore cipher")))
+}
+
+fn aad(address: &str) -> aead::Aad<Vec<u8>> {
+    aead::Aad::from(format!("{}:v{}:{}", AAD_DOMAIN, KEYSTORE_VERSION, address).into_bytes())
+}
+
+fn decode_hex(path: &str, value: &str) -> Result<Vec<u8>, CustomError> {
+    hex::decode(value).map_err(|e| CustomError::parse(path, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rng::deterministic_rng;
+
+    // Define the network type for the tests
+    type N = TestnetV0;
+
+    const PRIVATE_KEY: &str = "APrivateKey1zkp5LqRmm7535XfiX77VPQEgsS2Dj1B2DvH4QNP1UYrHEoR";
+    const ISSUER: &str = "aleo1ekyuzclmcw3aj7qncsxxaapxem82mgrd8zadgrrvl5k705zx6q9s7usuqy";
+
+    fn keystore() -> Keystore {
+        let private_key = PrivateKey::<N>::from_str(PRIVATE_KEY).unwrap();
+        Keystore::encrypt_with_rng(&private_key, "correct horse", MIN_ITERATIONS, &mut deterministic_rng(7)).unwrap()
+    }
+
+    #[test]
+    fn test_keystore_round_trip() {
+        let keystore = Keystore::from_json(&keystore().to_json().unwrap()).unwrap();
+        assert_eq!(keystore.version, KEYSTORE_VERSION);
+        assert_eq!(keystore.address, ISSUER);
+        assert_eq!(keystore.decrypt::<N>("correct horse").unwrap().to_string(), PRIVATE_KEY);
+
+        let json: JsonValue = serde_json::from_str(&keystore.to_json().unwrap()).unwrap();
+        assert_eq!(json["kdf"]["name"], "pbkdf2-hmac-sha256");
+        assert_eq!(json["cipher"]["name"], "aes-256-gcm");
+        assert!(!json.to_string().contains(PRIVATE_KEY));
+    }
+
+    #[test]
+    fn test_keystore_rejects_wrong_passphrase_and_tampering() {
+        let keystore = keystore();
+        assert_eq!(keystore.decrypt::<N>("wrong horse").unwrap_err().code(), "INVALID_KEY");
+
+        let mut other_address = keystore.clone();
+        other_address.address = "aleo14w44zfrehup9g894j7tgeyz5gsjuxn0nfn09vd2fvpznrg85rs8skywkte".to_string();
+        assert_eq!(other_address.decrypt::<N>("correct horse").unwrap_err().code(), "INVALID_KEY");
+
+        let mut future = keystore.clone();
+        future.version = 2;
+        assert_eq!(future.decrypt::<N>("correct horse").unwrap_err().path(), Some("version"));
+
+        let mut weak = keystore;
+        weak.kdf = KeystoreKdf::Pbkdf2HmacSha256 { iterations: 1, salt: "00".to_string() };
+        assert!(matches!(weak.decrypt::<N>("correct horse"), Err(CustomError::ConstraintViolation { .. })));
+        assert!(Keystore::from_json(r#"{ "version": 1 }"#).is_err());
+    }
+}
@@ -6,9 +6,47 @@ mod helpers;
 pub mod wasm;
 mod error;
 mod merkle_tree;
+pub mod credential_tree;
+pub mod attribute_tree;
+pub mod definitions;
+pub mod issuance;
+pub mod transition_inputs;
+pub mod programs;
+pub mod rng;
+pub mod schema;
+pub mod strings;
+pub mod values;
+pub mod batch;
+pub mod verification;
+pub mod issuer;
+pub mod keystore;
+pub mod domain;
+pub mod commitments;
+pub mod hiding;
+pub mod records;
+pub mod finder;
 
 // Crate level imports
 pub use wasm::*;
+pub use helpers::{ConsoleLogger, Logger, StdoutLogger};
+pub use merkle_tree::{Bhp256Hasher, MerkleHashAlgorithm, MerkleHasher, MerkleTree, Poseidon2Hasher, Poseidon4Hasher, SumHasher, hash_inputs_size_8, hash_values, hash_values_size_8, sign_root, sign_root_with_rng};
+pub use credential_tree::{CredentialTree, MerkleConfig, MerkleMultiproof, MerklePadding, MerkleProof, MerkleRoot};
+pub use attribute_tree::{AttributeCredential, AttributeLeaf, AttributeMultiproof, AttributeProof, AttributeTree};
+pub use schema::{CredentialSchema, MemberConstraint, MemberType, SchemaMember};
+pub use strings::{StringEncoding, decode_string, encode_string, hash_string};
+pub use values::ParseMode;
+pub use verification::{CredentialVerification, VerificationStatus};
+pub use batch::{BatchCredential, BatchError, BatchResult};
+pub use issuer::{Issuer, MerkleCredential};
+pub use keystore::{Keystore, KeystoreCipher, KeystoreKdf};
+pub use domain::SigningDomain;
+pub use commitments::InvalidationLayout;
+pub use finder::{FoundRecord, RecordFilter, RecordFinder, RecordScan};
+pub use programs::ZPassProgram;
+pub use records::{HidingZPass, MerkleZPass, PlainZPass, ZPass, ZPassRecord};
+pub use definitions::{schemas_from_aleo, schemas_from_leo};
+pub use issuance::{IssuedCredential, SaltSource};
+pub use transition_inputs::{InputVisibility, TransitionInput, TransitionInputs, ZPassTransition};
 
 // Standard library imports
 use std::convert::TryFrom;
@@ -18,7 +56,7 @@ use std::str::FromStr;
 use snarkvm_console::{
     prelude::Parser, account::{PrivateKey, Signature}, network::{environment::ToFields, TestnetV0, MainnetV0, Network as NetworkNative}, prelude::Zero, program::{Identifier, Literal, Plaintext, Value}, types::{*, field::Add}
 };
-use snarkvm_utilities::{TestRng, ToBits, Uniform};
+use snarkvm_utilities::{ToBits, Uniform};
 
 use indexmap::IndexMap;
 use wasm_bindgen::prelude::*;
@@ -28,8 +66,9 @@ use anyhow::anyhow;
 
 // Internal module imports
 use crate::error::CustomError;
+use crate::rng::{secure_rng, SigningRng};
 use crate::helpers::{
-    ConsoleLogger, Logger, create_hash, generate_message_with_addresses_and_fields,
+    create_hash, generate_message_with_addresses_and_fields, parse_private_key,
     sign_message_with_private_key, string_to_field, string_to_value_fields,
     verify_signature_with_address_and_message, convert_data_to_struct
 };
@@ -42,6 +81,7 @@ struct Credential<N: NetworkNative> {
 /// Signs the provided message using the given private key.
 ///
 /// This function also logs various stages of the signing process using the provided logger.
+/// The signature nonce is drawn from the operating system CSPRNG, see [`rng::secure_rng`].
 ///
 /// # Parameters
 ///
@@ -54,29 +94,33 @@ struct Credential<N: NetworkNative> {
 ///
 /// A result with tuple of signature and hash as strings if successful, otherwise returns a `CustomError`.
 pub fn sign_message_with_logger<N: NetworkNative>(private_key: String, message: SignInboundMessage, hash: HashAlgorithm, logger: &dyn Logger) -> Result<(String, String), CustomError> {
-    let private_key = PrivateKey::<N>::from_str(&private_key)
-        .map_err(|e| anyhow!("Failed to parse private key: {}", e))?;
-    let issuer = Address::<N>::try_from(&private_key)
-        .map_err(|e| anyhow!("Failed to parse issuer address: {}", e))?;
+    sign_message_with_rng::<N, _>(private_key, message, hash, logger, &mut secure_rng())
+}
 
-    let data = convert_data_to_struct(message.data, logger);
+/// Signs the provided message like [`sign_message_with_logger`], sampling the nonce from `rng`.
+///
+/// Pass [`rng::deterministic_rng`] to produce reproducible test vectors.
+pub fn sign_message_with_rng<N: NetworkNative, R: SigningRng>(private_key: String, message: SignInboundMessage, hash: HashAlgorithm, logger: &dyn Logger, rng: &mut R) -> Result<(String, String), CustomError> {
+    let private_key = parse_private_key::<N>(&private_key)?;
+    sign_message_with_key(&private_key, &message, hash, logger, rng)
+}
 
-    logger.log(&format!("Income Hash Algo: {:?}", hash));
+/// Signs the provided message like [`sign_message_with_rng`] with an already parsed private key.
+pub(crate) fn sign_message_with_key<N: NetworkNative, R: SigningRng>(private_key: &PrivateKey<N>, message: &SignInboundMessage, hash: HashAlgorithm, logger: &dyn Logger, rng: &mut R) -> Result<(String, String), CustomError> {
+    let issuer = Address::<N>::try_from(private_key)
+        .map_err(|e| CustomError::InvalidKey(format!("Failed to derive issuer address: {}", e)))?;
 
-    let credential  = Credential {
-        data
-    };
+    logger.log(&format!("Income Hash Algo: {:?}", hash));
 
-    let credentials_message: Value<N> = generate_message_with_addresses_and_fields(credential)?;
-    let hash = create_hash(credentials_message.clone(), hash)?;
-    let mut rng = TestRng::default();
+    let credentials_message: Value<N> = credential_message(message)?;
+    let hash = credential_hash(&credentials_message, message, hash)?;
 
-    let hash_fields = string_to_value_fields(hash.to_string().as_str());
+    let hash_fields = string_to_value_fields(hash.to_string().as_str())?;
 
-    let (signature, _nonce) = sign_message_with_private_key(
-        &private_key,
+    let signature = sign_message_with_private_key(
+        private_key,
         hash_fields.as_slice(),
-        &mut rng
+        rng
     )?;
 
     let verified = verify_signature_with_address_and_message(
@@ -85,7 +129,9 @@ pub fn sign_message_with_logger<N: NetworkNative>(private_key: String, message:
         hash_fields.as_slice()
     );
 
-    assert!(verified, "Signature was not verified properly!");
+    if !verified {
+        return Err(CustomError::VerificationFailed("Signature was not verified properly".to_string()));
+    }
 
     logger.log(&format!("Message: {:?}", credentials_message));
     logger.log(&format!("Signature: {:?}", signature));
@@ -97,10 +143,32 @@ pub fn sign_message_with_logger<N: NetworkNative>(private_key: String, message:
 }
 
 
+/// Encodes the credential data into the struct `Value` that is hashed and signed.
+pub(crate) fn credential_message<N: NetworkNative>(message: &SignInboundMessage) -> Result<Value<N>, CustomError> {
+    // Encode against the schema when one is attached, otherwise infer types from the values
+    let data = match &message.schema {
+        Some(schema) => schema.encode::<N>(&message.data)?,
+        None => convert_data_to_struct(message.data.clone(), message.mode)?,
+    };
+
+    generate_message_with_addresses_and_fields(Credential { data })
+}
+
+/// Hashes the encoded credential, mixing in the signing domain when the message has one.
+pub(crate) fn credential_hash<N: NetworkNative>(credential: &Value<N>, message: &SignInboundMessage, hash: HashAlgorithm) -> Result<String, CustomError> {
+    let credential_hash = create_hash(credential.clone(), hash)?;
+    match &message.domain {
+        Some(domain) => domain.separate::<N>(&credential_hash, hash),
+        None => Ok(credential_hash),
+    }
+}
+
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use serde_json::json;
+    use snarkvm_console::program::LiteralType;
     use helpers::StdoutLogger;
     use crate::helpers::{create_hash, generate_message_with_addresses_and_fields, string_to_value};
 
@@ -126,9 +194,7 @@ mod tests {
             "dob": dob
         });
 
-        let message = SignInboundMessage {
-            data: json_value,
-        };
+        let message = SignInboundMessage::from_json(json_value);
         let result = sign_message_with_logger::<N>(private_key, message, HashAlgorithm::POSEIDON2, &StdoutLogger);
 
         assert!(result.is_ok());
@@ -136,10 +202,72 @@ mod tests {
         let (_, _) = result.unwrap();
     }
 
+    #[test]
+    fn test_sign_message_with_deterministic_rng() {
+        let (private_key, issuer, subject, dob) = get_test_data();
+        let message = SignInboundMessage::from_json(json!({ "issuer": issuer, "subject": subject, "dob": dob }));
+
+        let first = sign_message_with_rng::<N, _>(private_key.clone(), message.clone(), HashAlgorithm::POSEIDON2, &StdoutLogger, &mut rng::deterministic_rng(7)).unwrap();
+        let second = sign_message_with_rng::<N, _>(private_key.clone(), message.clone(), HashAlgorithm::POSEIDON2, &StdoutLogger, &mut rng::deterministic_rng(7)).unwrap();
+        assert_eq!(first, second, "The same seed should reproduce the same signature");
+
+        let secure_first = sign_message_with_logger::<N>(private_key.clone(), message.clone(), HashAlgorithm::POSEIDON2, &StdoutLogger).unwrap();
+        let secure_second = sign_message_with_logger::<N>(private_key, message, HashAlgorithm::POSEIDON2, &StdoutLogger).unwrap();
+        assert_eq!(secure_first.1, secure_second.1);
+        assert_ne!(secure_first.0, secure_second.0, "The secure default should use a fresh nonce per signature");
+    }
+
+    #[test]
+    fn test_sign_message_without_schema_sorts_members() {
+        let (private_key, issuer, subject, dob) = get_test_data();
+        let reordered = SignInboundMessage::from_json(json!({ "subject": subject, "issuer": issuer, "dob": dob }));
+        let (_, hash) = sign_message_with_logger::<N>(private_key, reordered, HashAlgorithm::POSEIDON2, &StdoutLogger).unwrap();
+        let expected = Value::<N>::from_str(&format!("{{ dob: {dob}, issuer: {issuer}, subject: {subject} }}")).unwrap();
+        assert_eq!(hash, create_hash(expected, HashAlgorithm::POSEIDON2).unwrap());
+    }
+
+    #[test]
+    fn test_sign_message_with_schema() {
+        let (private_key, issuer, subject, _dob) = get_test_data();
+        let schema = CredentialSchema::new("Credentials", vec![
+            SchemaMember::required("issuer", LiteralType::Address),
+            SchemaMember::required("subject", LiteralType::Address),
+            SchemaMember::required("dob", LiteralType::U32),
+        ]);
+
+        let valid = SignInboundMessage::from_json(json!({ "issuer": issuer, "subject": subject, "dob": "20000101u32" })).with_credential_schema(schema.clone());
+        let (_, hash) = sign_message_with_logger::<N>(private_key.clone(), valid, HashAlgorithm::POSEIDON2, &StdoutLogger).unwrap();
+        let expected = Value::<N>::from_str(&format!("{{ issuer: {issuer}, subject: {subject}, dob: 20000101u32 }}")).unwrap();
+        assert_eq!(hash, create_hash(expected, HashAlgorithm::POSEIDON2).unwrap());
+
+        let invalid = SignInboundMessage::from_json(json!({ "issuer": issuer, "subject": subject })).with_credential_schema(schema);
+        let result = sign_message_with_logger::<N>(private_key, invalid, HashAlgorithm::POSEIDON2, &StdoutLogger);
+        assert!(matches!(result, Err(CustomError::MissingMember(_))));
+    }
+
+    #[test]
+    fn test_sign_message_with_merkle_root() {
+        let private_key = "APrivateKey1zkp8CZNn3yeCseEtxuVPbDCwSyhGW6yZKUYKfgXmcpoGPWH".to_string();
+        let leaves = hash_inputs_size_8::<N>(vec!["20000101u32", "123field"]).unwrap();
+        let root = MerkleTree::<N>::new(leaves).unwrap().root().to_string();
+
+        let message = SignInboundMessage::from_json(json!({ "root": root })).with_mode(ParseMode::Strict);
+        let (_, hash) = sign_message_with_logger::<N>(private_key, message, HashAlgorithm::POSEIDON2, &StdoutLogger).unwrap();
+        let expected = Value::<N>::from_str(&format!("{{ root: {root} }}")).unwrap();
+        assert_eq!(hash, create_hash(expected, HashAlgorithm::POSEIDON2).unwrap());
+    }
+
+    #[test]
+    fn test_sign_message_with_invalid_key() {
+        let message = SignInboundMessage::from_json(json!({ "dob": "20000101u32" }));
+        let result = sign_message_with_logger::<N>("APrivateKey1invalid".to_string(), message, HashAlgorithm::POSEIDON2, &StdoutLogger);
+        assert_eq!(result.unwrap_err().code(), "INVALID_KEY");
+    }
+
     #[test]
     fn test_create_hash_with_different_messages_psd2() {
-        let message1 = string_to_value::<N>("123field");
-        let message2 = string_to_value::<N>("321field");
+        let message1 = string_to_value::<N>("123field").unwrap();
+        let message2 = string_to_value::<N>("321field").unwrap();
 
         let hash1 = create_hash(message1, HashAlgorithm::POSEIDON2).unwrap();
         let hash2 = create_hash(message2, HashAlgorithm::POSEIDON2).unwrap();
@@ -149,8 +277,8 @@ mod tests {
 
     #[test]
     fn test_create_hash_with_different_messages_bhp1024() {
-        let message1 = string_to_value::<N>("123field");
-        let message2 = string_to_value::<N>("321field");
+        let message1 = string_to_value::<N>("123field").unwrap();
+        let message2 = string_to_value::<N>("321field").unwrap();
 
         let hash1 = create_hash(message1, HashAlgorithm::BHP1024).unwrap();
         let hash2 = create_hash(message2, HashAlgorithm::BHP1024).unwrap();
@@ -160,7 +288,7 @@ mod tests {
 
     #[test]
     fn test_create_hash_with_same_messages_psd2() {
-        let message = string_to_value::<N>("123field");
+        let message = string_to_value::<N>("123field").unwrap();
 
 
         let hash1 = create_hash(message.clone(), HashAlgorithm::POSEIDON2).unwrap();
@@ -174,7 +302,7 @@ mod tests {
 
     #[test]
     fn test_create_hash_with_same_messages_bhp2014() {
-        let message = string_to_value::<N>("123field");
+        let message = string_to_value::<N>("123field").unwrap();
 
         let hash1 = create_hash(message.clone(), HashAlgorithm::BHP1024).unwrap();
         let hash2 = create_hash(message.clone(), HashAlgorithm::BHP1024).unwrap();
@@ -187,7 +315,7 @@ mod tests {
 
     #[test]
     fn test_create_hash_with_same_messages_sha3() {
-        let message = string_to_value::<N>("123field");
+        let message = string_to_value::<N>("123field").unwrap();
 
         let hash1 = create_hash(message.clone(), HashAlgorithm::SHA3_256).unwrap();
         let hash2 = create_hash(message.clone(), HashAlgorithm::SHA3_256).unwrap();
@@ -200,7 +328,7 @@ mod tests {
 
     #[test]
     fn test_create_hash_with_same_messages_keccak256() {
-        let message = string_to_value::<N>("123field");
+        let message = string_to_value::<N>("123field").unwrap();
 
         let hash1 = create_hash(message.clone(), HashAlgorithm::KECCAK256).unwrap();
         let hash2 = create_hash(message.clone(), HashAlgorithm::KECCAK256).unwrap();
@@ -223,7 +351,7 @@ mod tests {
         });
 
         let credential = Credential::<N> {
-            data: convert_data_to_struct(json_value, &StdoutLogger),
+            data: convert_data_to_struct(json_value, ParseMode::Lenient).unwrap(),
         };
 
         println!("{:?}", credential);
@@ -1,68 +1,206 @@
 use super::*;
+use crate::helpers::{field_array, hash_to_field_psd2};
+use crate::values::{parse_value, ParseMode};
+
+/// The node hash of a merkle tree, recorded next to the roots it produces.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MerkleHashAlgorithm {
+    /// `Poseidon2::hash_to_field(left + right)`, as `zpass_merkle_8.aleo` computes it. Siblings
+    /// commute and any two pairs with the same sum collide, so credential trees reject it.
+    #[default]
+    Sum = 0,
+    /// `Poseidon2::hash_to_field([left, right])` of a Leo `[field; 2]`.
+    Poseidon2 = 1,
+    /// `Poseidon4::hash_to_field([left, right])` of a Leo `[field; 2]`.
+    Poseidon4 = 2,
+    /// `BHP256::hash_to_field([left, right])` of a Leo `[field; 2]`.
+    Bhp256 = 3,
+}
+
+impl MerkleHashAlgorithm {
+    /// Whether swapping the children changes the parent, so proofs depend on direction bits.
+    pub fn is_ordered(&self) -> bool {
+        *self != Self::Sum
+    }
+}
+
+/// Hashes two children into their parent node.
+pub trait MerkleHasher<N: NetworkNative> {
+    /// The algorithm recorded next to roots produced with this hasher.
+    fn algorithm(&self) -> MerkleHashAlgorithm;
+
+    fn hash_pair(&self, left: &Field<N>, right: &Field<N>) -> Result<Field<N>, CustomError>;
+}
+
+/// [`MerkleHashAlgorithm::Sum`], the hasher of the fixed eight-leaf tree.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SumHasher;
+
+/// [`MerkleHashAlgorithm::Poseidon2`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Poseidon2Hasher;
+
+/// [`MerkleHashAlgorithm::Poseidon4`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Poseidon4Hasher;
+
+/// [`MerkleHashAlgorithm::Bhp256`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Bhp256Hasher;
+
+impl<N: NetworkNative> MerkleHasher<N> for SumHasher {
+    fn algorithm(&self) -> MerkleHashAlgorithm {
+        MerkleHashAlgorithm::Sum
+    }
+
+    fn hash_pair(&self, left: &Field<N>, right: &Field<N>) -> Result<Field<N>, CustomError> {
+        let sum = left.add(right);
+        let value = Value::<N>::from(Literal::Field(sum)).to_fields()
+            .map_err(|e| CustomError::HashFailure(format!("Failed value to Fields conversion: {}", e)))?;
+        N::hash_psd2(value.as_slice())
+            .map_err(|e| CustomError::HashFailure(format!("Failed hash_psd2 conversion: {}", e)))
+    }
+}
+
+impl<N: NetworkNative> MerkleHasher<N> for Poseidon2Hasher {
+    fn algorithm(&self) -> MerkleHashAlgorithm {
+        MerkleHashAlgorithm::Poseidon2
+    }
+
+    fn hash_pair(&self, left: &Field<N>, right: &Field<N>) -> Result<Field<N>, CustomError> {
+        hash_to_field_psd2(field_array(&[*left, *right]))
+    }
+}
+
+impl<N: NetworkNative> MerkleHasher<N> for Poseidon4Hasher {
+    fn algorithm(&self) -> MerkleHashAlgorithm {
+        MerkleHashAlgorithm::Poseidon4
+    }
+
+    fn hash_pair(&self, left: &Field<N>, right: &Field<N>) -> Result<Field<N>, CustomError> {
+        let fields = Value::Plaintext(field_array(&[*left, *right])).to_fields()
+            .map_err(|e| CustomError::HashFailure(format!("Failed value to Fields conversion: {}", e)))?;
+        N::hash_psd4(&fields)
+            .map_err(|e| CustomError::HashFailure(format!("Failed hash_psd4 conversion: {}", e)))
+    }
+}
+
+impl<N: NetworkNative> MerkleHasher<N> for Bhp256Hasher {
+    fn algorithm(&self) -> MerkleHashAlgorithm {
+        MerkleHashAlgorithm::Bhp256
+    }
+
+    fn hash_pair(&self, left: &Field<N>, right: &Field<N>) -> Result<Field<N>, CustomError> {
+        N::hash_bhp256(&Value::Plaintext(field_array(&[*left, *right])).to_bits_le())
+            .map_err(|e| CustomError::HashFailure(format!("Failed hash_bhp256 conversion: {}", e)))
+    }
+}
+
+impl<N: NetworkNative> MerkleHasher<N> for MerkleHashAlgorithm {
+    fn algorithm(&self) -> MerkleHashAlgorithm {
+        *self
+    }
+
+    fn hash_pair(&self, left: &Field<N>, right: &Field<N>) -> Result<Field<N>, CustomError> {
+        match self {
+            Self::Sum => SumHasher.hash_pair(left, right),
+            Self::Poseidon2 => Poseidon2Hasher.hash_pair(left, right),
+            Self::Poseidon4 => Poseidon4Hasher.hash_pair(left, right),
+            Self::Bhp256 => Bhp256Hasher.hash_pair(left, right),
+        }
+    }
+}
 
 #[derive(Debug, Clone)]
 pub struct MerkleTree<N: NetworkNative> {
     root: Field<N>,
     levels: Vec<Vec<Field<N>>>,
+    algorithm: MerkleHashAlgorithm,
 }
 
 impl<N: NetworkNative> MerkleTree<N> {
-    // Helper function to compute hash of sum of two fields
-    fn hash_field_sum(a: &Field<N>, b: &Field<N>) -> Result<Field<N>, CustomError> {
-        let sum = a.add(b);
-        let value = Value::<N>::from(Literal::Field(sum)).to_fields()
-            .map_err(CustomError::from)?;
-        N::hash_psd2(value.as_slice()).map_err(CustomError::from)
+    /// Builds a tree with [`SumHasher`], matching `zpass_merkle_8.aleo`.
+    pub fn new(inputs: Vec<Field<N>>) -> Result<Self, CustomError> {
+        Self::with_hasher(inputs, &SumHasher)
     }
 
-    pub fn new(inputs: Vec<Field<N>>) -> Result<Self, CustomError> {
+    /// Builds a tree whose nodes are `hasher.hash_pair(left, right)`.
+    pub fn with_hasher<H: MerkleHasher<N>>(inputs: Vec<Field<N>>, hasher: &H) -> Result<Self, CustomError> {
+        // Every level is paired up, so the leaf count must be a power of two
+        if !inputs.len().is_power_of_two() {
+            return Err(CustomError::parse("leaves", format!("Expected a power of two number of leaves, found {}", inputs.len())));
+        }
+
         // Initialize tree levels array
         let mut levels: Vec<Vec<Field<N>>> = Vec::new();
         
         // Level 0: Input leaves
-        levels.push(inputs);
+        let mut current_level = inputs;
         
         // Build tree bottom-up until we reach a single root node
-        while levels.last().unwrap().len() > 1 {
-            let current_level = levels.last().unwrap();
-            let mut next_level = Vec::new();
+        while current_level.len() > 1 {
+            let mut next_level = Vec::with_capacity(current_level.len() / 2);
             
-            for chunk in current_level.chunks(2) {
-                let hash = Self::hash_field_sum(&chunk[0], &chunk[1])?;
+            for pair in current_level.chunks_exact(2) {
+                let hash = hasher.hash_pair(&pair[0], &pair[1])?;
                 next_level.push(hash);
             }
             
-            levels.push(next_level);
+            levels.push(current_level);
+            current_level = next_level;
         }
 
-        // Root is the last element in the last level
-        let root = levels.last().unwrap()[0];
+        // Root is the only element in the last level
+        let root = current_level[0];
+        levels.push(current_level);
         
-        Ok(Self { root, levels })
+        Ok(Self { root, levels, algorithm: hasher.algorithm() })
     }
 
     pub fn get_proof(&self, index: usize) -> Result<Vec<Field<N>>, CustomError> {
-        let mut proof = Vec::new();
+        let leaves = self.levels[0].len();
+        if index >= leaves {
+            return Err(CustomError::IndexOutOfRange { index, len: leaves });
+        }
+
+        let mut proof = Vec::with_capacity(self.levels.len() - 1);
         let mut current_index = index;
 
         // Skip the last level (root) by using .len() - 1
         for level in &self.levels[..self.levels.len() - 1] {
-            if current_index % 2 == 0 {
-                proof.push(level[current_index + 1]);
-            } else {
-                proof.push(level[current_index - 1]);
-            }
+            // XOR with 1 selects the sibling on either side
+            proof.push(level[current_index ^ 1]);
             current_index /= 2;
         }
 
         Ok(proof)
     }
 
+    /// Checks a proof from [`MerkleTree::get_proof`]. Only the commutative [`SumHasher`] can
+    /// do without the leaf position; ordered trees are checked with [`MerkleTree::verify_proof_at`].
     pub fn verify_proof(&self, leaf: Field<N>, proof: &Vec<Field<N>>) -> Result<bool, CustomError> {
+        if self.algorithm.is_ordered() {
+            return Err(CustomError::parse("proof", "Ordered merkle trees need the leaf index to verify a proof"));
+        }
         let mut current_hash = leaf;
         for proof_element in proof {
-            current_hash = Self::hash_field_sum(&current_hash, proof_element)?;
-            println!("Current hash: {}", current_hash);
+            current_hash = self.algorithm.hash_pair(&current_hash, proof_element)?;
+        }
+
+        Ok(current_hash == self.root)
+    }
+
+    /// Checks a proof from [`MerkleTree::get_proof`] for the leaf at `index`, whose bits give
+    /// the side of every node from the leaves up.
+    pub fn verify_proof_at(&self, index: usize, leaf: Field<N>, proof: &[Field<N>]) -> Result<bool, CustomError> {
+        let mut current_hash = leaf;
+        for (level, sibling) in proof.iter().enumerate() {
+            current_hash = match (index >> level) & 1 == 1 {
+                true => self.algorithm.hash_pair(sibling, &current_hash)?,
+                false => self.algorithm.hash_pair(&current_hash, sibling)?,
+            };
         }
 
         Ok(current_hash == self.root)
@@ -75,25 +213,36 @@ impl<N: NetworkNative> MerkleTree<N> {
     pub fn levels(&self) -> &Vec<Vec<Field<N>>> {
         &self.levels
     }
+
+    /// The node hash the tree was built with.
+    pub fn algorithm(&self) -> MerkleHashAlgorithm {
+        self.algorithm
+    }
 }
 
 pub fn sign_root<N: NetworkNative>(private_key: &str, root: &str) -> Result<String, CustomError> {
-    if !private_key.starts_with("APrivateKey1") {
-        return Err(CustomError::from(anyhow::anyhow!("Private key must start with APrivateKey1")));
-    }
+    sign_root_with_rng::<N, _>(private_key, root, &mut secure_rng())
+}
 
+/// Signs a merkle root like [`sign_root`], sampling the nonce from `rng`.
+pub fn sign_root_with_rng<N: NetworkNative, R: SigningRng>(private_key: &str, root: &str, rng: &mut R) -> Result<String, CustomError> {
+    let private_key = parse_private_key::<N>(private_key)?;
+    sign_root_with_key(&private_key, root, rng)
+}
+
+/// Signs a merkle root like [`sign_root_with_rng`] with an already parsed private key.
+pub(crate) fn sign_root_with_key<N: NetworkNative, R: SigningRng>(private_key: &PrivateKey<N>, root: &str, rng: &mut R) -> Result<String, CustomError> {
     if !root.ends_with("field") {
-        return Err(CustomError::from(anyhow::anyhow!("Root must end with 'field'")));
+        return Err(CustomError::parse("root", "Root must end with 'field'"));
     }
-    let private_key = PrivateKey::<N>::from_str(private_key)?;
-    let issuer = Address::<N>::try_from(&private_key)?;
-    let hash_fields = string_to_value_fields::<N>(root);
-    let mut rng = TestRng::default();
+    let issuer = Address::<N>::try_from(private_key)
+        .map_err(|e| CustomError::InvalidKey(format!("Failed to derive issuer address: {}", e)))?;
+    let hash_fields = string_to_value_fields::<N>(root)?;
 
-    let (signature, _nonce) = sign_message_with_private_key(
-        &private_key,
+    let signature = sign_message_with_private_key(
+        private_key,
         hash_fields.as_slice(),
-        &mut rng
+        rng
     )?;
 
     let verified = verify_signature_with_address_and_message(
@@ -101,135 +250,50 @@ pub fn sign_root<N: NetworkNative>(private_key: &str, root: &str) -> Result<Stri
         &issuer,
         hash_fields.as_slice()
     );
-    assert!(verified, "Signature was not verified properly!");
+    if !verified {
+        return Err(CustomError::VerificationFailed("Signature was not verified properly".to_string()));
+    }
 
     Ok(signature.to_string())
 }
 
+/// Hashes up to eight Aleo literals into merkle leaves, padding with zero leaves.
+///
+/// Inputs are parsed in [`ParseMode::Lenient`], see [`hash_values_size_8`] for typed values
+/// and strict parsing.
 pub fn hash_inputs_size_8<N: NetworkNative>(inputs: Vec<&str>) -> Result<Vec<Field<N>>, CustomError> {
-    let mut res = Vec::with_capacity(8);
-    let mut last_i= 0;
-    for (i, s) in inputs.into_iter().enumerate() {
-        let hash = match s {
-            s if s.starts_with("aleo1") => {
-                let address = Address::<N>::from_str(s)
-                    .unwrap_or_else(|e| panic!("Failed to parse Aleo address: {}", e));
-                let fields = Value::<N>::from(Literal::Address(address)).to_fields()
-                    .map_err(CustomError::from)?;
-                N::hash_psd2(fields.as_slice())?
-            }
-            s if s.ends_with("field") => {
-                let (_, field) = Field::<N>::parse(s)
-                    .unwrap_or_else(|e| panic!("Failed to parse field: {}", e));
-                let fields = string_to_value_fields(field.to_string().as_str());
-                N::hash_psd2(&fields)?
-            }
-            s if s.ends_with("u8") => {
-                let (_, num) = U8::<N>::parse(s)
-                    .unwrap_or_else(|e| panic!("Failed to parse U8: {}", e));
-                let value = Value::<N>::try_from(Literal::U8(num))
-                    .unwrap_or_else(|e| panic!("Failed to convert U8 to Value: {}", e));
-                N::hash_psd2(value.to_fields()?.as_slice())?
-            }
-            s if s.ends_with("u16") => {
-                let (_, num) = U16::<N>::parse(s)
-                    .unwrap_or_else(|e| panic!("Failed to parse U16: {}", e));
-                let value = Value::<N>::try_from(Literal::U16(num))
-                    .unwrap_or_else(|e| panic!("Failed to convert U16 to Value: {}", e));
-                N::hash_psd2(value.to_fields()?.as_slice())?
-            }
-            s if s.ends_with("u32") => {
-                let (_, num) = U32::<N>::parse(s)
-                    .unwrap_or_else(|e| panic!("Failed to parse U32: {}", e));
-                let value = Value::<N>::try_from(Literal::U32(num))
-                    .unwrap_or_else(|e| panic!("Failed to convert U32 to Value: {}", e));
-                N::hash_psd2(value.to_fields()?.as_slice())?
-            }
-            s if s.ends_with("u64") => {
-                let (_, num) = U64::<N>::parse(s)
-                    .unwrap_or_else(|e| panic!("Failed to parse U64: {}", e));
-                let value = Value::<N>::try_from(Literal::U64(num))
-                    .unwrap_or_else(|e| panic!("Failed to convert U64 to Value: {}", e));
-                N::hash_psd2(value.to_fields()?.as_slice())?
-            }
-            s if s.ends_with("u128") => {
-                let (_, num) = U128::<N>::parse(s)
-                    .unwrap_or_else(|e| panic!("Failed to parse U128: {}", e));
-                let value = Value::<N>::try_from(Literal::U128(num))
-                    .unwrap_or_else(|e| panic!("Failed to convert U128 to Value: {}", e));
-                N::hash_psd2(value.to_fields()?.as_slice())?
-            }
-            s if s.ends_with("i8") => {
-                let (_, num) = I8::<N>::parse(s)
-                    .unwrap_or_else(|e| panic!("Failed to parse I8: {}", e));
-                let value = Value::<N>::try_from(Literal::I8(num))
-                    .unwrap_or_else(|e| panic!("Failed to convert I8 to Value: {}", e));
-                N::hash_psd2(value.to_fields()?.as_slice())?
-            }
-            s if s.ends_with("i16") => {
-                let (_, num) = I16::<N>::parse(s)
-                    .unwrap_or_else(|e| panic!("Failed to parse I16: {}", e));
-                let value = Value::<N>::try_from(Literal::I16(num))
-                    .unwrap_or_else(|e| panic!("Failed to convert I16 to Value: {}", e));
-                N::hash_psd2(value.to_fields()?.as_slice())?
-            }
-            s if s.ends_with("i32") => {
-                let (_, num) = I32::<N>::parse(s)
-                    .unwrap_or_else(|e| panic!("Failed to parse I32: {}", e));
-                let value = Value::<N>::try_from(Literal::I32(num))
-                    .unwrap_or_else(|e| panic!("Failed to convert I328 to Value: {}", e));
-                N::hash_psd2(value.to_fields()?.as_slice())?
-            }
-            s if s.ends_with("i64") => {
-                let (_, num) = I64::<N>::parse(s)
-                    .unwrap_or_else(|e| panic!("Failed to parse I64: {}", e));
-                let value = Value::<N>::try_from(Literal::I64(num))
-                    .unwrap_or_else(|e| panic!("Failed to convert I64 to Value: {}", e));
-                N::hash_psd2(value.to_fields()?.as_slice())?
-            }
-            s if s.ends_with("i128") => {
-                let (_, num) = I128::<N>::parse(s)
-                    .unwrap_or_else(|e| panic!("Failed to parse I128: {}", e));
-                let value = Value::<N>::try_from(Literal::I128(num))
-                    .unwrap_or_else(|e| panic!("Failed to convert I128 to Value: {}", e));
-                N::hash_psd2(value.to_fields()?.as_slice())?
-            }
-            s if s.ends_with("scalar") => {
-                let (_, scalar) = Scalar::<N>::parse(s)
-                    .unwrap_or_else(|e| panic!("Failed to parse Scalar: {}", e));
-                let value = Value::<N>::try_from(Literal::Scalar(scalar))
-                    .unwrap_or_else(|e| panic!("Failed to convert Scalar to Value: {}", e));
-                N::hash_psd2(value.to_fields()?.as_slice())?
-            }
-            s if s.ends_with("group") => {
-                let (_, group) = Group::<N>::parse(s)
-                    .unwrap_or_else(|e| panic!("Failed to parse Group: {}", e));
-                let value = Value::<N>::try_from(Literal::Group(group))
-                    .unwrap_or_else(|e| panic!("Failed to convert Group to Value: {}", e));
-                N::hash_psd2(value.to_fields()?.as_slice())?
-            }
-            s if s == "true" || s == "false" => {
-                let (_, boolean) = Boolean::<N>::parse(s)
-                    .unwrap_or_else(|e| panic!("Failed to parse Boolean: {}", e));
-                let value = Value::<N>::try_from(Literal::Boolean(boolean))
-                    .unwrap_or_else(|e| panic!("Failed to convert Boolean to Value: {}", e));
-                N::hash_psd2(value.to_fields()?.as_slice())?
-            }
-            _ => {
-                panic!("unsupported type");
-            }
-        };
-        res.push(hash);
-        last_i = i;
-    }
-    if last_i < 8 {
-        for _ in 0..8 - (last_i + 1) {
-            res.push(Field::<N>::zero());
-        }
+    let values: Vec<JsonValue> = inputs.into_iter().map(|s| JsonValue::String(s.to_string())).collect();
+    hash_values_size_8::<N>(&values, ParseMode::Lenient)
+}
+
+/// Hashes up to eight credential values into merkle leaves with `Poseidon2::hash_to_field`.
+///
+/// Values are parsed exactly like credential data when signing, so they may be Aleo literal
+/// strings, booleans or typed values such as `{ "type": "u32", "value": 20000101 }`.
+pub fn hash_values_size_8<N: NetworkNative>(values: &[JsonValue], mode: ParseMode) -> Result<Vec<Field<N>>, CustomError> {
+    if values.len() > 8 {
+        return Err(CustomError::parse("inputs", format!("Expected at most 8 inputs, found {}", values.len())));
     }
+    let mut res = hash_values::<N>(values, mode)?;
+    // Unused leaves are zero so the tree always has exactly eight leaves
+    res.resize(8, Field::<N>::zero());
     Ok(res)
 }
 
+/// Hashes any number of credential values into merkle leaves like [`hash_values_size_8`],
+/// without padding.
+pub fn hash_values<N: NetworkNative>(values: &[JsonValue], mode: ParseMode) -> Result<Vec<Field<N>>, CustomError> {
+    let mut res = Vec::with_capacity(values.len());
+    for (i, value) in values.iter().enumerate() {
+        let plaintext = parse_value::<N>(&format!("inputs[{}]", i), value, mode)?;
+        let fields = Value::<N>::Plaintext(plaintext).to_fields()
+            .map_err(|e| CustomError::HashFailure(format!("Failed value to Fields conversion: {}", e)))?;
+        let hash = N::hash_psd2(fields.as_slice())
+            .map_err(|e| CustomError::HashFailure(format!("Failed hash_psd2 conversion: {}", e)))?;
+        res.push(hash);
+    }
+    Ok(res)
+}
 
 #[cfg(test)]
 mod tests {
@@ -285,4 +349,40 @@ mod tests {
         assert!(res.len() == 8);
         println!("Result: {:?}", res);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_invalid_inputs_return_errors() {
+        let tree = MerkleTree::<N>::new(hash_inputs_size_8::<N>(TEST_INPUTS.to_vec()).unwrap()).unwrap();
+        assert!(matches!(tree.get_proof(8), Err(CustomError::IndexOutOfRange { index: 8, len: 8 })));
+        assert!(matches!(MerkleTree::<N>::new(Vec::new()), Err(CustomError::Parse { .. })));
+        assert!(matches!(MerkleTree::<N>::new(vec![Field::<N>::zero(); 3]), Err(CustomError::Parse { .. })));
+
+        assert_eq!(hash_inputs_size_8::<N>(vec!["1u8"; 9]).unwrap_err().path(), Some("inputs"));
+        assert!(matches!(hash_inputs_size_8::<N>(vec!["123field", "300u8"]), Err(CustomError::Parse { path, .. }) if path == "inputs[1]"));
+        assert!(matches!(hash_values_size_8::<N>(&[JsonValue::from("hello")], ParseMode::Strict), Err(CustomError::Parse { .. })));
+        assert_eq!(hash_inputs_size_8::<N>(Vec::new()).unwrap(), vec![Field::<N>::zero(); 8]);
+
+        let private_key = "APrivateKey1zkp8CZNn3yeCseEtxuVPbDCwSyhGW6yZKUYKfgXmcpoGPWH";
+        assert_eq!(sign_root::<N>("not a key", "1field").unwrap_err().code(), "INVALID_KEY");
+        assert_eq!(sign_root::<N>(private_key, "1u8").unwrap_err().code(), "PARSE_ERROR");
+    }
+
+    #[test]
+    fn test_hash_values_matches_hash_inputs() {
+        let typed = [
+            serde_json::json!("aleo1rhgdu77hgyqd3xjj8ucu3jj9r2krwz6mnzyd80gncr5fxcwlh5rsvzp9px"),
+            serde_json::json!({ "type": "field", "value": "123" }),
+            serde_json::json!({ "type": "u8", "value": 23 }),
+            serde_json::json!({ "type": "u128", "value": 33 }),
+            serde_json::json!("123123scalar"),
+            serde_json::json!("0group"),
+        ];
+        let leaves = hash_values_size_8::<N>(&typed, ParseMode::Strict).unwrap();
+        assert_eq!(leaves, hash_inputs_size_8::<N>(TEST_INPUTS.to_vec()).unwrap());
+
+        // Plain text is a field in lenient mode, matching the signer
+        let text = hash_inputs_size_8::<N>(vec!["Hawaiiu8"]).unwrap();
+        let field = string_to_field::<N>(Some("Hawaiiu8".to_string())).unwrap();
+        assert_eq!(text[0], N::hash_psd2(&Value::<N>::from(Literal::Field(field)).to_fields().unwrap()).unwrap());
+    }
+}
@@ -0,0 +1,95 @@
+use super::*;
+
+pub(crate) const VERIFY_POSEIDON2: &str = include_str!("../../programs/verify_poseidon2/src/main.leo");
+pub(crate) const VERIFY_POSEIDON2_ZPASS: &str = include_str!("../../programs/verify_poseidon2_zpass/src/main.leo");
+pub(crate) const ZPASS_HIDING: &str = include_str!("../../programs/zpass_hiding/src/main.leo");
+pub(crate) const ZPASS_INVALIDATE: &str = include_str!("../../programs/zpass_invalidate/src/main.leo");
+pub(crate) const ZPASS_INVALIDATE_HIDING: &str = include_str!("../../programs/zpass_invalidate_hiding/src/main.leo");
+pub(crate) const ZPASS_MERKLE_8: &str = include_str!("../../programs/zpass_merkle_8/src/main.leo");
+
+/// The ZPass programs, serialized as their program ids.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ZPassProgram {
+    #[serde(rename = "verify_poseidon2_zpass.aleo")]
+    VerifyPoseidon2ZPass = 0,
+    #[serde(rename = "zpass_hiding.aleo")]
+    ZPassHiding = 1,
+    #[serde(rename = "zpass_invalidate.aleo")]
+    ZPassInvalidate = 2,
+    #[serde(rename = "zpass_invalidate_hiding.aleo")]
+    ZPassInvalidateHiding = 3,
+    #[serde(rename = "zpass_merkle_8.aleo")]
+    ZPassMerkle8 = 4,
+    /// Verifies a signature without issuing a record.
+    #[serde(rename = "verify_poseidon2.aleo")]
+    VerifyPoseidon2 = 5,
+}
+
+impl ZPassProgram {
+    pub const ALL: [ZPassProgram; 6] = [
+        Self::VerifyPoseidon2ZPass,
+        Self::ZPassHiding,
+        Self::ZPassInvalidate,
+        Self::ZPassInvalidateHiding,
+        Self::ZPassMerkle8,
+        Self::VerifyPoseidon2,
+    ];
+
+    pub fn program_id(&self) -> &'static str {
+        match self {
+            Self::VerifyPoseidon2ZPass => "verify_poseidon2_zpass.aleo",
+            Self::ZPassHiding => "zpass_hiding.aleo",
+            Self::ZPassInvalidate => "zpass_invalidate.aleo",
+            Self::ZPassInvalidateHiding => "zpass_invalidate_hiding.aleo",
+            Self::ZPassMerkle8 => "zpass_merkle_8.aleo",
+            Self::VerifyPoseidon2 => "verify_poseidon2.aleo",
+        }
+    }
+
+    /// The Leo source of the program.
+    pub(crate) fn program_source(&self) -> &'static str {
+        match self {
+            Self::VerifyPoseidon2ZPass => VERIFY_POSEIDON2_ZPASS,
+            Self::ZPassHiding => ZPASS_HIDING,
+            Self::ZPassInvalidate => ZPASS_INVALIDATE,
+            Self::ZPassInvalidateHiding => ZPASS_INVALIDATE_HIDING,
+            Self::ZPassMerkle8 => ZPASS_MERKLE_8,
+            Self::VerifyPoseidon2 => VERIFY_POSEIDON2,
+        }
+    }
+
+    /// Whether the program issues a `ZPass` record.
+    pub fn issues_record(&self) -> bool {
+        *self != Self::VerifyPoseidon2
+    }
+
+    /// Whether the `ZPass` record stores the attributes as group commitments.
+    pub fn is_hiding(&self) -> bool {
+        matches!(self, Self::ZPassHiding | Self::ZPassInvalidateHiding)
+    }
+
+    /// Returns the schema of the struct or record called `name` in the program source.
+    pub fn schema(&self, name: &str) -> Result<CredentialSchema, CustomError> {
+        CredentialSchema::from_leo(self.program_source(), name)
+    }
+
+    /// Returns the schema of the program's `ZPass` record, `owner` first.
+    pub fn record_schema(&self) -> Result<CredentialSchema, CustomError> {
+        match self.issues_record() {
+            true => self.schema("ZPass"),
+            false => Err(CustomError::parse("program", format!("{} does not issue a ZPass record", self.program_id()))),
+        }
+    }
+}
+
+impl FromStr for ZPassProgram {
+    type Err = CustomError;
+
+    /// Parses a program id such as `zpass_hiding.aleo`.
+    fn from_str(program_id: &str) -> Result<Self, Self::Err> {
+        Self::ALL.into_iter()
+            .find(|program| program.program_id() == program_id)
+            .ok_or_else(|| CustomError::parse(program_id, "Unsupported program"))
+    }
+}
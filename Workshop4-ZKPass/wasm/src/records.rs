@@ -0,0 +1,294 @@
+use super::*;
+use snarkvm_console::account::ViewKey;
+use snarkvm_console::program::{Ciphertext, Entry, Record};
+
+/// `ZPass` of `verify_poseidon2_zpass.aleo` and `zpass_invalidate.aleo`, holding the plain attributes.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(bound = "")]
+pub struct PlainZPass<N: NetworkNative> {
+    pub owner: Address<N>,
+    pub issuer: Address<N>,
+    pub dob: U32<N>,
+    pub nationality: Field<N>,
+    pub expiry: U32<N>,
+    pub salt: Scalar<N>,
+}
+
+/// `ZPass` of `zpass_hiding.aleo` and `zpass_invalidate_hiding.aleo`, where every attribute is
+/// `BHP256::commit_to_group(value, salt)`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(bound = "")]
+pub struct HidingZPass<N: NetworkNative> {
+    pub owner: Address<N>,
+    pub issuer: Group<N>,
+    pub dob: Group<N>,
+    pub nationality: Group<N>,
+    pub expiry: Group<N>,
+    pub salt: Scalar<N>,
+}
+
+/// `ZPass` of `zpass_merkle_8.aleo`, holding the signed Merkle root of the attributes.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(bound = "")]
+pub struct MerkleZPass<N: NetworkNative> {
+    pub owner: Address<N>,
+    pub issuer: Address<N>,
+    pub root: Field<N>,
+}
+
+/// The members of a `ZPass` record, by layout.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(bound = "", tag = "layout", rename_all = "snake_case")]
+pub enum ZPass<N: NetworkNative> {
+    Plain(PlainZPass<N>),
+    Hiding(HidingZPass<N>),
+    Merkle(MerkleZPass<N>),
+}
+
+impl<N: NetworkNative> ZPass<N> {
+    pub fn owner(&self) -> &Address<N> {
+        match self {
+            Self::Plain(zpass) => &zpass.owner,
+            Self::Hiding(zpass) => &zpass.owner,
+            Self::Merkle(zpass) => &zpass.owner,
+        }
+    }
+
+    /// Returns the plain attributes, failing for the hiding and merkle layouts.
+    pub fn into_plain(self) -> Result<PlainZPass<N>, CustomError> {
+        match self {
+            Self::Plain(zpass) => Ok(zpass),
+            _ => Err(CustomError::parse("record", "Expected a ZPass record with plain attributes")),
+        }
+    }
+}
+
+/// A decrypted `ZPass` record of `program`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(bound = "")]
+pub struct ZPassRecord<N: NetworkNative> {
+    pub program: ZPassProgram,
+    pub nonce: Group<N>,
+    pub zpass: ZPass<N>,
+}
+
+impl<N: NetworkNative> ZPassRecord<N> {
+    /// Decrypts `record` with `view_key`, see [`decrypt_record`], and parses it as the `ZPass` of `program`.
+    pub fn decrypt(program: ZPassProgram, record: &str, view_key: &ViewKey<N>) -> Result<Self, CustomError> {
+        Self::from_record(program, &decrypt_record(record, view_key)?)
+    }
+
+    /// Parses a decrypted record as the `ZPass` of `program`. The members must match the record
+    /// definition of the program source, in order.
+    pub fn from_record(program: ZPassProgram, record: &Record<N, Plaintext<N>>) -> Result<Self, CustomError> {
+        let members = record_members(record);
+        let schema = program.record_schema()?;
+        for name in members.keys() {
+            if !schema.members().iter().any(|member| &member.name == name) {
+                return Err(CustomError::UnexpectedMember(name.clone()));
+            }
+        }
+        for (i, member) in schema.members().iter().enumerate() {
+            match members.get_index_of(&member.name) {
+                None => return Err(CustomError::MissingMember(member.name.clone())),
+                Some(index) if index != i => return Err(CustomError::ConstraintViolation {
+                    member: member.name.clone(),
+                    reason: format!("Expected member {} of the record, found member {}", i, index),
+                }),
+                Some(_) => {}
+            }
+        }
+
+        let zpass = match program {
+            ZPassProgram::VerifyPoseidon2ZPass | ZPassProgram::ZPassInvalidate => ZPass::Plain(PlainZPass {
+                owner: member(&members, "owner")?,
+                issuer: member(&members, "issuer")?,
+                dob: member(&members, "dob")?,
+                nationality: member(&members, "nationality")?,
+                expiry: member(&members, "expiry")?,
+                salt: member(&members, "salt")?,
+            }),
+            ZPassProgram::ZPassHiding | ZPassProgram::ZPassInvalidateHiding => ZPass::Hiding(HidingZPass {
+                owner: member(&members, "owner")?,
+                issuer: member(&members, "issuer")?,
+                dob: member(&members, "dob")?,
+                nationality: member(&members, "nationality")?,
+                expiry: member(&members, "expiry")?,
+                salt: member(&members, "salt")?,
+            }),
+            ZPassProgram::ZPassMerkle8 => ZPass::Merkle(MerkleZPass {
+                owner: member(&members, "owner")?,
+                issuer: member(&members, "issuer")?,
+                root: member(&members, "root")?,
+            }),
+            ZPassProgram::VerifyPoseidon2 => return Err(CustomError::parse("program", "verify_poseidon2.aleo does not issue a ZPass record")),
+        };
+        Ok(Self { program, nonce: *record.nonce(), zpass })
+    }
+}
+
+/// Decrypts a record with `view_key`.
+///
+/// `record` is either a `record1...` ciphertext or a plaintext record with visibility suffixes,
+/// e.g. `{ owner: aleo1...private, ..., _nonce: ...group.public }`. Both fail with `INVALID_KEY`
+/// unless the record is owned by the address of `view_key`.
+pub fn decrypt_record<N: NetworkNative>(record: &str, view_key: &ViewKey<N>) -> Result<Record<N, Plaintext<N>>, CustomError> {
+    let record = record.trim();
+    let plaintext = match record.starts_with("record1") {
+        true => {
+            let ciphertext = Record::<N, Ciphertext<N>>::from_str(record)
+                .map_err(|e| CustomError::parse("record", e))?;
+            if !ciphertext.is_owner(view_key) {
+                return Err(CustomError::InvalidKey("The view key does not own the record".to_string()));
+            }
+            ciphertext.decrypt(view_key)
+                .map_err(|e| CustomError::InvalidKey(format!("Failed to decrypt the record: {}", e)))?
+        }
+        false => Record::<N, Plaintext<N>>::from_str(record)
+            .map_err(|e| CustomError::parse("record", e))?,
+    };
+
+    let owner = view_key.to_address();
+    if **plaintext.owner() != owner {
+        return Err(CustomError::InvalidKey("The view key does not own the record".to_string()));
+    }
+    Ok(plaintext)
+}
+
+/// Parses an `AViewKey1...` string.
+pub fn parse_view_key<N: NetworkNative>(view_key: &str) -> Result<ViewKey<N>, CustomError> {
+    ViewKey::<N>::from_str(view_key)
+        .map_err(|e| CustomError::InvalidKey(format!("Failed to parse view key: {}", e)))
+}
+
+/// Returns the members of a decrypted record, `owner` first, without visibilities.
+pub fn record_members<N: NetworkNative>(record: &Record<N, Plaintext<N>>) -> IndexMap<String, Plaintext<N>> {
+    let mut members = IndexMap::with_capacity(record.data().len() + 1);
+    members.insert("owner".to_string(), Plaintext::from(Literal::Address(**record.owner())));
+    for (name, entry) in record.data() {
+        let (Entry::Constant(value) | Entry::Public(value) | Entry::Private(value)) = entry;
+        members.insert(name.to_string(), value.clone());
+    }
+    members
+}
+
+/// A record member type that is a single literal.
+trait RecordLiteral<N: NetworkNative>: Sized {
+    const TYPE: &'static str;
+
+    fn from_literal(literal: &Literal<N>) -> Option<Self>;
+}
+
+macro_rules! impl_record_literal {
+    ($($ty:ident => $name:literal),* $(,)?) => {
+        $(
+            impl<N: NetworkNative> RecordLiteral<N> for $ty<N> {
+                const TYPE: &'static str = $name;
+
+                fn from_literal(literal: &Literal<N>) -> Option<Self> {
+                    match literal {
+                        Literal::$ty(value) => Some(*value),
+                        _ => None,
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_record_literal! {
+    Address => "address",
+    Field => "field",
+    Group => "group",
+    Scalar => "scalar",
+    U32 => "u32",
+}
+
+fn member<N: NetworkNative, T: RecordLiteral<N>>(members: &IndexMap<String, Plaintext<N>>, name: &str) -> Result<T, CustomError> {
+    let value = members.get(name).ok_or_else(|| CustomError::MissingMember(name.to_string()))?;
+    match value {
+        Plaintext::Literal(literal, _) => T::from_literal(literal),
+        _ => None,
+    }
+    .ok_or_else(|| CustomError::MistypedMember { member: name.to_string(), expected: T::TYPE.to_string(), found: value.to_string() })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rng::deterministic_rng;
+
+    // Define the network type for the tests
+    type N = TestnetV0;
+
+    const PRIVATE_KEY: &str = "APrivateKey1zkp5LqRmm7535XfiX77VPQEgsS2Dj1B2DvH4QNP1UYrHEoR";
+    const OWNER: &str = "aleo1ekyuzclmcw3aj7qncsxxaapxem82mgrd8zadgrrvl5k705zx6q9s7usuqy";
+
+    fn view_key(private_key: &str) -> ViewKey<N> {
+        ViewKey::try_from(PrivateKey::<N>::from_str(private_key).unwrap()).unwrap()
+    }
+
+    fn other_view_key() -> ViewKey<N> {
+        ViewKey::try_from(PrivateKey::<N>::new(&mut deterministic_rng(1)).unwrap()).unwrap()
+    }
+
+    /// Returns a plaintext record with `entries` and the nonce of randomizer `r`.
+    fn plaintext_record(entries: &str, r: &Scalar<N>) -> Record<N, Plaintext<N>> {
+        let nonce = N::g_scalar_multiply(r);
+        Record::from_str(&format!("{{ owner: {OWNER}.private, {entries}, _nonce: {nonce}.public }}")).unwrap()
+    }
+
+    const PLAIN_ENTRIES: &str = "issuer: aleo1ekyuzclmcw3aj7qncsxxaapxem82mgrd8zadgrrvl5k705zx6q9s7usuqy.private, dob: 20000101u32.private, nationality: 123field.private, expiry: 20300101u32.private, salt: 42scalar.private";
+
+    #[test]
+    fn test_decrypt_plain_zpass_ciphertext() {
+        let r = Scalar::<N>::from_str("7scalar").unwrap();
+        let plaintext = plaintext_record(PLAIN_ENTRIES, &r);
+        let ciphertext = plaintext.encrypt(r).unwrap().to_string();
+        assert!(ciphertext.starts_with("record1"));
+
+        let record = ZPassRecord::<N>::decrypt(ZPassProgram::VerifyPoseidon2ZPass, &ciphertext, &view_key(PRIVATE_KEY)).unwrap();
+        assert_eq!(record.nonce, *plaintext.nonce());
+        let ZPass::Plain(zpass) = &record.zpass else { panic!("expected the plain layout") };
+        assert_eq!(zpass.owner.to_string(), OWNER);
+        assert_eq!(*zpass.dob, 20000101);
+        assert_eq!(zpass.nationality.to_string(), "123field");
+        assert_eq!(zpass.salt.to_string(), "42scalar");
+
+        let json = serde_json::to_value(&record).unwrap();
+        assert_eq!(json["program"], "verify_poseidon2_zpass.aleo");
+        assert_eq!(json["zpass"]["layout"], "plain");
+        assert_eq!(json["zpass"]["expiry"], "20300101u32");
+
+        // Only the owner can decrypt the record
+        let error = decrypt_record::<N>(&ciphertext, &other_view_key()).unwrap_err();
+        assert_eq!(error.code(), "INVALID_KEY");
+        let error = decrypt_record::<N>(&plaintext.to_string(), &other_view_key()).unwrap_err();
+        assert_eq!(error.code(), "INVALID_KEY");
+    }
+
+    #[test]
+    fn test_parse_hiding_and_merkle_zpass_plaintext() {
+        let r = Scalar::<N>::from_str("9scalar").unwrap();
+        let hiding = plaintext_record("issuer: 0group.private, dob: 0group.private, nationality: 0group.private, expiry: 0group.private, salt: 42scalar.private", &r);
+        let record = ZPassRecord::<N>::decrypt(ZPassProgram::ZPassHiding, &hiding.to_string(), &view_key(PRIVATE_KEY)).unwrap();
+        assert!(matches!(record.zpass, ZPass::Hiding(ref zpass) if zpass.dob == Group::zero()));
+
+        let merkle = plaintext_record("issuer: aleo1ekyuzclmcw3aj7qncsxxaapxem82mgrd8zadgrrvl5k705zx6q9s7usuqy.private, root: 5field.private", &r);
+        let record = ZPassRecord::<N>::from_record(ZPassProgram::ZPassMerkle8, &merkle).unwrap();
+        assert_eq!(record.zpass.owner().to_string(), OWNER);
+        assert!(matches!(record.zpass, ZPass::Merkle(ref zpass) if zpass.root.to_string() == "5field"));
+        assert_eq!(record.zpass.into_plain().unwrap_err().path(), Some("record"));
+
+        // The layout must match the program
+        let plain = plaintext_record(PLAIN_ENTRIES, &r);
+        assert!(matches!(ZPassRecord::from_record(ZPassProgram::ZPassMerkle8, &plain), Err(CustomError::UnexpectedMember(_))));
+        assert!(matches!(ZPassRecord::from_record(ZPassProgram::VerifyPoseidon2ZPass, &merkle), Err(CustomError::UnexpectedMember(_))));
+        let partial = plaintext_record("issuer: aleo1ekyuzclmcw3aj7qncsxxaapxem82mgrd8zadgrrvl5k705zx6q9s7usuqy.private", &r);
+        assert!(matches!(ZPassRecord::from_record(ZPassProgram::ZPassMerkle8, &partial), Err(CustomError::MissingMember(_))));
+        let error = ZPassRecord::from_record(ZPassProgram::ZPassHiding, &plain).unwrap_err();
+        assert_eq!((error.code(), error.path()), ("MISTYPED_MEMBER", Some("issuer")));
+        assert_eq!(ZPassRecord::from_record(ZPassProgram::VerifyPoseidon2, &merkle).unwrap_err().path(), Some("program"));
+        assert_eq!("zpass_merkle_8.aleo".parse::<ZPassProgram>().unwrap(), ZPassProgram::ZPassMerkle8);
+    }
+}
@@ -0,0 +1,46 @@
+use rand::{rngs::OsRng, CryptoRng, Rng, SeedableRng};
+use rand_chacha::ChaCha20Rng;
+
+/// Marker trait for random number generators accepted by the signing functions.
+///
+/// Any cryptographically secure generator qualifies; the nonce of every Schnorr
+/// signature is sampled from it, so a predictable generator leaks the issuer key.
+pub trait SigningRng: Rng + CryptoRng {}
+
+impl<R: Rng + CryptoRng> SigningRng for R {}
+
+/// Returns the default signing generator.
+///
+/// This is the operating system CSPRNG: `getrandom` natively and
+/// `crypto.getRandomValues` when compiled to wasm.
+pub fn secure_rng() -> OsRng {
+    OsRng
+}
+
+/// Returns a seeded ChaCha20 generator for reproducible test vectors.
+///
+/// The same seed always produces the same signatures. Never use it with a real issuer key.
+pub fn deterministic_rng(seed: u64) -> ChaCha20Rng {
+    ChaCha20Rng::seed_from_u64(seed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deterministic_rng_is_reproducible() {
+        let a: [u8; 32] = deterministic_rng(42).gen();
+        let b: [u8; 32] = deterministic_rng(42).gen();
+        let c: [u8; 32] = deterministic_rng(43).gen();
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+    }
+
+    #[test]
+    fn test_secure_rng_is_not_constant() {
+        let a: [u8; 32] = secure_rng().gen();
+        let b: [u8; 32] = secure_rng().gen();
+        assert_ne!(a, b);
+    }
+}
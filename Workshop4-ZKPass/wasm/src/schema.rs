@@ -0,0 +1,573 @@
+use super::*;
+use core::cmp::Ordering;
+use core::fmt;
+use crate::helpers::insert_to_map;
+use snarkvm_console::program::LiteralType;
+use crate::strings::StringEncoding;
+use crate::values::is_canonical;
+
+/// Ordered description of a Leo struct used to validate and encode credential data.
+///
+/// The member order, names and types must match the Leo struct that the program hashes,
+/// e.g. `FullCredentials` in `verify_poseidon2_zpass.aleo`, otherwise
+/// `Poseidon2::hash_to_field` produces a different hash than the signer.
+#[wasm_bindgen]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CredentialSchema {
+    pub(crate) name: String,
+    pub(crate) members: Vec<SchemaMember>,
+}
+
+/// A single member of a [`CredentialSchema`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SchemaMember {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub member_type: MemberType,
+    /// Optional members that are absent from the data are encoded as the zero value of their type.
+    #[serde(default = "default_required")]
+    pub required: bool,
+    #[serde(default)]
+    pub constraints: Vec<MemberConstraint>,
+}
+
+/// The Leo type of a schema member.
+///
+/// In JSON a literal is written as its type name (`"u32"`), a nested struct as
+/// `{ "struct": <schema> }`, an array as `{ "element": <type>, "length": 4 }` and a UTF-8
+/// string as `{ "text": { "chunks": 4 } }` or `{ "text": "hash" }`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum MemberType {
+    Literal(LiteralType),
+    Struct {
+        #[serde(rename = "struct")]
+        schema: Box<CredentialSchema>,
+    },
+    Array { element: Box<MemberType>, length: u32 },
+    Text {
+        #[serde(rename = "text")]
+        encoding: StringEncoding,
+    },
+}
+
+/// A restriction on the value of a schema member.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum MemberConstraint {
+    /// Inclusive bounds for integer members, written as plain numbers or Aleo literals.
+    Range { min: Option<String>, max: Option<String> },
+    /// The value must equal one of the listed Aleo literals.
+    OneOf { values: Vec<String> },
+}
+
+fn default_required() -> bool {
+    true
+}
+
+impl MemberType {
+    /// Creates a nested struct type.
+    pub fn structure(schema: CredentialSchema) -> Self {
+        Self::Struct { schema: Box::new(schema) }
+    }
+
+    /// Creates a fixed-length array type.
+    pub fn array(element: impl Into<MemberType>, length: u32) -> Self {
+        Self::Array { element: Box::new(element.into()), length }
+    }
+
+    /// Creates a UTF-8 string type stored as `[field; N]` chunks or a single hashed field.
+    pub fn text(encoding: StringEncoding) -> Self {
+        Self::Text { encoding }
+    }
+
+    /// Returns the literal type, if this is a literal member.
+    pub fn literal_type(&self) -> Option<LiteralType> {
+        match self {
+            Self::Literal(literal_type) => Some(*literal_type),
+            _ => None,
+        }
+    }
+}
+
+impl From<LiteralType> for MemberType {
+    fn from(literal_type: LiteralType) -> Self {
+        Self::Literal(literal_type)
+    }
+}
+
+impl fmt::Display for MemberType {
+    /// Prints the type in Leo syntax, e.g. `u32`, `Credentials` or `[address; 4]`.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Literal(literal_type) => write!(f, "{}", literal_type),
+            Self::Struct { schema } => write!(f, "{}", schema.name),
+            Self::Array { element, length } => write!(f, "[{}; {}]", element, length),
+            Self::Text { encoding: StringEncoding::Chunks(length) } => write!(f, "[field; {}]", length),
+            Self::Text { encoding: StringEncoding::Hash } => write!(f, "field"),
+        }
+    }
+}
+
+impl SchemaMember {
+    /// Creates a member that must be present in the credential data.
+    pub fn required(name: &str, member_type: impl Into<MemberType>) -> Self {
+        Self { name: name.to_string(), member_type: member_type.into(), required: true, constraints: Vec::new() }
+    }
+
+    /// Creates a member that defaults to the zero value of its type when absent.
+    pub fn optional(name: &str, member_type: impl Into<MemberType>) -> Self {
+        Self { name: name.to_string(), member_type: member_type.into(), required: false, constraints: Vec::new() }
+    }
+
+    /// Adds a constraint to the member.
+    pub fn with_constraint(mut self, constraint: MemberConstraint) -> Self {
+        self.constraints.push(constraint);
+        self
+    }
+}
+
+impl CredentialSchema {
+    /// Creates a schema from its struct name and ordered members.
+    pub fn new(name: &str, members: Vec<SchemaMember>) -> Self {
+        Self { name: name.to_string(), members }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn members(&self) -> &[SchemaMember] {
+        &self.members
+    }
+
+    /// Validates the credential data against the schema and encodes it in schema order.
+    ///
+    /// The data must be a JSON object whose keys appear in the same relative order as the
+    /// schema members. Literal values are Aleo literals (`"20000101u32"`), bare numbers
+    /// (`"20000101"` or `20000101`) that take their type from the schema, or JSON booleans.
+    /// Nested structs are JSON objects and arrays are JSON arrays of the declared length.
+    pub fn encode<N: NetworkNative>(&self, data: &JsonValue) -> Result<IndexMap<String, Plaintext<N>>, CustomError> {
+        if !data.is_object() {
+            return Err(CustomError::parse("data", "Credential data must be a JSON object"));
+        }
+        self.encode_members("", data)
+    }
+
+    fn encode_members<N: NetworkNative>(&self, path: &str, data: &JsonValue) -> Result<IndexMap<String, Plaintext<N>>, CustomError> {
+        let object = data.as_object().ok_or_else(|| CustomError::MistypedMember {
+            member: path.to_string(),
+            expected: self.name.clone(),
+            found: data.to_string(),
+        })?;
+
+        // Reject unknown members and members that appear out of schema order.
+        let mut previous: Option<(usize, &String)> = None;
+        for key in object.keys() {
+            let position = self.members.iter().position(|member| &member.name == key)
+                .ok_or_else(|| CustomError::UnexpectedMember(member_path(path, key)))?;
+            if let Some((previous_position, previous_key)) = previous {
+                if position < previous_position {
+                    return Err(CustomError::MisorderedMember { member: member_path(path, key), after: previous_key.clone() });
+                }
+            }
+            previous = Some((position, key));
+        }
+
+        let mut members = IndexMap::with_capacity(self.members.len());
+        for member in &self.members {
+            let path = member_path(path, &member.name);
+            let plaintext = match object.get(&member.name) {
+                Some(value) => encode_value::<N>(&path, &member.member_type, value)?,
+                None if member.required => return Err(CustomError::MissingMember(path)),
+                None => zero_plaintext::<N>(&path, &member.member_type)?,
+            };
+            member.check_constraints(&path, &plaintext)?;
+            members.insert(member.name.clone(), plaintext);
+        }
+        Ok(members)
+    }
+}
+
+fn member_path(parent: &str, member: &str) -> String {
+    match parent.is_empty() {
+        true => member.to_string(),
+        false => format!("{}.{}", parent, member),
+    }
+}
+
+fn encode_value<N: NetworkNative>(path: &str, member_type: &MemberType, value: &JsonValue) -> Result<Plaintext<N>, CustomError> {
+    let mistyped = || CustomError::MistypedMember {
+        member: path.to_string(),
+        expected: member_type.to_string(),
+        found: value.to_string(),
+    };
+
+    match member_type {
+        MemberType::Literal(literal_type) => parse_json_literal::<N>(value, *literal_type)
+            .map(Plaintext::from)
+            .ok_or_else(mistyped),
+        MemberType::Struct { schema } => struct_plaintext(schema.encode_members::<N>(path, value)?),
+        MemberType::Array { element, length } => {
+            let items = value.as_array().ok_or_else(mistyped)?;
+            if items.len() != *length as usize {
+                return Err(mistyped());
+            }
+            let elements = items.iter().enumerate()
+                .map(|(i, item)| encode_value::<N>(&format!("{}[{}]", path, i), element, item))
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(Plaintext::Array(elements, Default::default()))
+        }
+        MemberType::Text { encoding } => encoding.encode::<N>(path, value.as_str().ok_or_else(mistyped)?),
+    }
+}
+
+impl SchemaMember {
+    fn check_constraints<N: NetworkNative>(&self, path: &str, plaintext: &Plaintext<N>) -> Result<(), CustomError> {
+        if self.constraints.is_empty() {
+            return Ok(());
+        }
+        let violation = |reason: String| CustomError::ConstraintViolation { member: path.to_string(), reason };
+        let (Plaintext::Literal(literal, _), MemberType::Literal(literal_type)) = (plaintext, &self.member_type) else {
+            return Err(violation(format!("constraints require a literal type, found {}", self.member_type)));
+        };
+        let literal_type = *literal_type;
+
+        for constraint in &self.constraints {
+            match constraint {
+                MemberConstraint::Range { min, max } => {
+                    let value = IntegerValue::from_literal(literal)
+                        .ok_or_else(|| violation(format!("range constraints require an integer type, found {}", literal_type)))?;
+                    if let Some(min) = min {
+                        let bound = IntegerValue::from_bound(min, literal_type)
+                            .ok_or_else(|| violation(format!("invalid minimum `{}`", min)))?;
+                        if value.cmp(&bound) == Ordering::Less {
+                            return Err(violation(format!("{} is below the minimum {}", literal, min)));
+                        }
+                    }
+                    if let Some(max) = max {
+                        let bound = IntegerValue::from_bound(max, literal_type)
+                            .ok_or_else(|| violation(format!("invalid maximum `{}`", max)))?;
+                        if value.cmp(&bound) == Ordering::Greater {
+                            return Err(violation(format!("{} is above the maximum {}", literal, max)));
+                        }
+                    }
+                }
+                MemberConstraint::OneOf { values } => {
+                    let allowed = values.iter()
+                        .filter_map(|value| parse_typed_literal::<N>(value, literal_type))
+                        .any(|allowed| &allowed == literal);
+                    if !allowed {
+                        return Err(violation(format!("{} is not one of {:?}", literal, values)));
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Wraps encoded members into a `Plaintext::Struct`.
+pub(crate) fn struct_plaintext<N: NetworkNative>(members: IndexMap<String, Plaintext<N>>) -> Result<Plaintext<N>, CustomError> {
+    let mut map = IndexMap::with_capacity(members.len());
+    for (key, value) in members {
+        insert_to_map(&mut map, &key, value)?;
+    }
+    Ok(Plaintext::Struct(map, Default::default()))
+}
+
+/// Returns the literal type and value of a `{ "type": "u32", "value": 20000101 }` annotation.
+pub(crate) fn typed_annotation(value: &JsonValue) -> Option<(LiteralType, &JsonValue)> {
+    let object = value.as_object()?;
+    if object.len() != 2 {
+        return None;
+    }
+    let literal_type = LiteralType::from_str(object.get("type")?.as_str()?).ok()?;
+    Some((literal_type, object.get("value")?))
+}
+
+/// Parses a JSON string, number, boolean or type annotation as a literal of `literal_type`.
+pub(crate) fn parse_json_literal<N: NetworkNative>(value: &JsonValue, literal_type: LiteralType) -> Option<Literal<N>> {
+    match value {
+        JsonValue::String(text) => parse_typed_literal::<N>(text, literal_type),
+        JsonValue::Number(number) if is_numeric_type(literal_type) => parse_typed_literal::<N>(&number.to_string(), literal_type),
+        JsonValue::Bool(boolean) if literal_type == LiteralType::Boolean => Some(Literal::Boolean(Boolean::new(*boolean))),
+        JsonValue::Object(_) => {
+            let (annotated, value) = typed_annotation(value)?;
+            (annotated == literal_type).then(|| parse_json_literal::<N>(value, literal_type))?
+        }
+        _ => None,
+    }
+}
+
+/// Parses `text` as a literal of `literal_type`, appending the type suffix to bare numbers.
+pub(crate) fn parse_typed_literal<N: NetworkNative>(text: &str, literal_type: LiteralType) -> Option<Literal<N>> {
+    let (text, literal) = match Literal::<N>::from_str(text) {
+        Ok(literal) => (text.to_string(), literal),
+        Err(_) if is_numeric_type(literal_type) => {
+            let text = format!("{}{}", text, literal_type);
+            let literal = Literal::<N>::from_str(&text).ok()?;
+            (text, literal)
+        }
+        Err(_) => return None,
+    };
+    (literal.to_type() == literal_type && is_canonical(&text, &literal)).then_some(literal)
+}
+
+fn is_numeric_type(literal_type: LiteralType) -> bool {
+    !matches!(literal_type, LiteralType::Address | LiteralType::Boolean | LiteralType::Signature | LiteralType::String)
+}
+
+/// Returns the value used for absent optional members.
+fn zero_plaintext<N: NetworkNative>(path: &str, member_type: &MemberType) -> Result<Plaintext<N>, CustomError> {
+    match member_type {
+        MemberType::Literal(literal_type) => zero_literal::<N>(path, *literal_type).map(Plaintext::from),
+        MemberType::Struct { schema } => {
+            let mut members = IndexMap::with_capacity(schema.members.len());
+            for member in &schema.members {
+                members.insert(member.name.clone(), zero_plaintext::<N>(&member_path(path, &member.name), &member.member_type)?);
+            }
+            struct_plaintext(members)
+        }
+        MemberType::Array { element, length } => {
+            let elements = (0..*length).map(|i| zero_plaintext::<N>(&format!("{}[{}]", path, i), element)).collect::<Result<Vec<_>, _>>()?;
+            Ok(Plaintext::Array(elements, Default::default()))
+        }
+        // An absent string is encoded as the empty string
+        MemberType::Text { encoding } => encoding.encode::<N>(path, ""),
+    }
+}
+
+fn zero_literal<N: NetworkNative>(path: &str, literal_type: LiteralType) -> Result<Literal<N>, CustomError> {
+    let literal = match literal_type {
+        LiteralType::Address => Literal::Address(Address::new(Group::zero())),
+        LiteralType::Boolean => Literal::Boolean(Boolean::new(false)),
+        LiteralType::Signature | LiteralType::String => {
+            return Err(CustomError::UnsupportedType { member: path.to_string(), ty: literal_type.to_string() });
+        }
+        numeric => Literal::from_str(&format!("0{}", numeric)).map_err(|e| CustomError::parse(path, e))?,
+    };
+    Ok(literal)
+}
+
+/// Integer value used to compare literals against range bounds.
+/// `Large` only holds values above `i128::MAX`, so the derived ordering is numeric.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum IntegerValue {
+    Signed(i128),
+    Large(u128),
+}
+
+impl IntegerValue {
+    fn from_literal<N: NetworkNative>(literal: &Literal<N>) -> Option<Self> {
+        if !is_integer_type(literal.to_type()) {
+            return None;
+        }
+        let text = literal.to_string();
+        Self::from_digits(text.trim_end_matches(literal.to_type().type_name()))
+    }
+
+    fn from_bound(bound: &str, literal_type: LiteralType) -> Option<Self> {
+        Self::from_digits(bound.trim_end_matches(literal_type.type_name()))
+    }
+
+    fn from_digits(digits: &str) -> Option<Self> {
+        match digits.parse::<i128>() {
+            Ok(value) => Some(Self::Signed(value)),
+            Err(_) => digits.parse::<u128>().ok().map(Self::Large),
+        }
+    }
+}
+
+fn is_integer_type(literal_type: LiteralType) -> bool {
+    matches!(
+        literal_type,
+        LiteralType::U8 | LiteralType::U16 | LiteralType::U32 | LiteralType::U64 | LiteralType::U128
+            | LiteralType::I8 | LiteralType::I16 | LiteralType::I32 | LiteralType::I64 | LiteralType::I128
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    // Define the network type for the tests
+    type N = TestnetV0;
+
+    const ISSUER: &str = "aleo1ekyuzclmcw3aj7qncsxxaapxem82mgrd8zadgrrvl5k705zx6q9s7usuqy";
+    const SUBJECT: &str = "aleo14w44zfrehup9g894j7tgeyz5gsjuxn0nfn09vd2fvpznrg85rs8skywkte";
+
+    fn full_credentials() -> CredentialSchema {
+        CredentialSchema::new("FullCredentials", vec![
+            SchemaMember::required("issuer", LiteralType::Address),
+            SchemaMember::required("subject", LiteralType::Address),
+            SchemaMember::required("dob", LiteralType::U32),
+            SchemaMember::required("nationality", LiteralType::Field),
+            SchemaMember::required("expiry", LiteralType::U32),
+            SchemaMember::required("salt", LiteralType::Scalar),
+        ])
+    }
+
+    #[test]
+    fn test_encode_matches_leo_struct() {
+        let data = json!({
+            "issuer": ISSUER,
+            "subject": SUBJECT,
+            "dob": "20000101u32",
+            "nationality": "123field",
+            "expiry": "20300101",
+            "salt": "5scalar"
+        });
+        let members = full_credentials().encode::<N>(&data).unwrap();
+        let names: Vec<&str> = members.keys().map(|k| k.as_str()).collect();
+        assert_eq!(names, ["issuer", "subject", "dob", "nationality", "expiry", "salt"]);
+        assert_eq!(members["expiry"].to_string(), "20300101u32");
+
+        let expected = Value::<N>::from_str(&format!(
+            "{{ issuer: {ISSUER}, subject: {SUBJECT}, dob: 20000101u32, nationality: 123field, expiry: 20300101u32, salt: 5scalar }}"
+        )).unwrap();
+        let encoded = generate_message_with_addresses_and_fields(Credential { data: members }).unwrap();
+        assert_eq!(create_hash(encoded, HashAlgorithm::POSEIDON2).unwrap(), create_hash(expected, HashAlgorithm::POSEIDON2).unwrap());
+    }
+
+    #[test]
+    fn test_encode_rejects_invalid_data() {
+        let schema = full_credentials();
+
+        let missing = json!({ "issuer": ISSUER, "subject": SUBJECT, "dob": "1u32", "nationality": "1field", "expiry": "1u32" });
+        assert!(matches!(schema.encode::<N>(&missing), Err(CustomError::MissingMember(name)) if name == "salt"));
+
+        let extra = json!({ "issuer": ISSUER, "name": "Alice" });
+        assert!(matches!(schema.encode::<N>(&extra), Err(CustomError::UnexpectedMember(name)) if name == "name"));
+
+        let mistyped = json!({ "issuer": ISSUER, "subject": SUBJECT, "dob": "1u64", "nationality": "1field", "expiry": "1u32", "salt": "1scalar" });
+        assert!(matches!(schema.encode::<N>(&mistyped), Err(CustomError::MistypedMember { member, .. }) if member == "dob"));
+
+        let misordered = json!({ "subject": SUBJECT, "issuer": ISSUER });
+        assert!(matches!(schema.encode::<N>(&misordered), Err(CustomError::MisorderedMember { member, .. }) if member == "issuer"));
+    }
+
+    #[test]
+    fn test_optional_members_and_constraints() {
+        let schema = CredentialSchema::new("Credentials", vec![
+            SchemaMember::required("dob", LiteralType::U32)
+                .with_constraint(MemberConstraint::Range { min: Some("19000101".to_string()), max: Some("20991231u32".to_string()) }),
+            SchemaMember::required("level", LiteralType::U8)
+                .with_constraint(MemberConstraint::OneOf { values: vec!["1u8".to_string(), "2u8".to_string()] }),
+            SchemaMember::optional("expiry", LiteralType::U32),
+        ]);
+
+        let members = schema.encode::<N>(&json!({ "dob": "20000101u32", "level": "2u8" })).unwrap();
+        assert_eq!(members["expiry"].to_string(), "0u32");
+
+        let too_old = schema.encode::<N>(&json!({ "dob": "18000101u32", "level": "2u8" }));
+        assert!(matches!(too_old, Err(CustomError::ConstraintViolation { member, .. }) if member == "dob"));
+
+        let bad_level = schema.encode::<N>(&json!({ "dob": "20000101u32", "level": "3u8" }));
+        assert!(matches!(bad_level, Err(CustomError::ConstraintViolation { member, .. }) if member == "level"));
+    }
+
+    #[test]
+    fn test_encode_full_range_fields() {
+        let schema = CredentialSchema::new("Rooted", vec![
+            SchemaMember::required("root", LiteralType::Field),
+            SchemaMember::required("salt", LiteralType::Scalar),
+        ]);
+        let root = "8444461749428370424248824938781546531375899335154063827935233455917409239040";
+        let members = schema.encode::<N>(&json!({ "root": root, "salt": "5scalar" })).unwrap();
+        assert_eq!(members["root"].to_string(), format!("{}field", root));
+
+        let overflow = json!({ "root": "8444461749428370424248824938781546531375899335154063827935233455917409239041field", "salt": "5scalar" });
+        assert!(matches!(schema.encode::<N>(&overflow), Err(CustomError::MistypedMember { member, .. }) if member == "root"));
+        let overflow = json!({ "root": "1field", "salt": "2111115437357092606062206234695386632838870926408408195193685246394721360383" });
+        assert!(matches!(schema.encode::<N>(&overflow), Err(CustomError::MistypedMember { member, .. }) if member == "salt"));
+    }
+
+    #[test]
+    fn test_encode_nested_structs_and_arrays() {
+        let addresses = CredentialSchema::new("Addresses", vec![
+            SchemaMember::required("home", LiteralType::Address),
+            SchemaMember::required("verified", LiteralType::Boolean),
+        ]);
+        let schema = CredentialSchema::new("Credentials", vec![
+            SchemaMember::required("issuer", LiteralType::Address),
+            SchemaMember::required("dob", LiteralType::U32),
+            SchemaMember::required("block", MemberType::structure(addresses)),
+            SchemaMember::required("delegates", MemberType::array(LiteralType::Address, 2)),
+        ]);
+
+        let data = json!({
+            "issuer": ISSUER,
+            "dob": 20000101,
+            "block": { "home": SUBJECT, "verified": true },
+            "delegates": [ISSUER, SUBJECT]
+        });
+        let members = schema.encode::<N>(&data).unwrap();
+        let encoded = generate_message_with_addresses_and_fields(Credential { data: members }).unwrap();
+        let expected = Value::<N>::from_str(&format!(
+            "{{ issuer: {ISSUER}, dob: 20000101u32, block: {{ home: {SUBJECT}, verified: true }}, delegates: [{ISSUER}, {SUBJECT}] }}"
+        )).unwrap();
+        assert_eq!(create_hash(encoded, HashAlgorithm::POSEIDON2).unwrap(), create_hash(expected, HashAlgorithm::POSEIDON2).unwrap());
+
+        let short_array = json!({ "issuer": ISSUER, "dob": 1, "block": { "home": SUBJECT, "verified": true }, "delegates": [ISSUER] });
+        assert!(matches!(schema.encode::<N>(&short_array), Err(CustomError::MistypedMember { member, .. }) if member == "delegates"));
+
+        let nested_error = json!({ "issuer": ISSUER, "dob": 1, "block": { "home": SUBJECT, "verified": "yes" }, "delegates": [ISSUER, SUBJECT] });
+        assert!(matches!(schema.encode::<N>(&nested_error), Err(CustomError::MistypedMember { member, .. }) if member == "block.verified"));
+
+        let element_error = json!({ "issuer": ISSUER, "dob": 1, "block": { "home": SUBJECT, "verified": true }, "delegates": [ISSUER, 5] });
+        assert!(matches!(schema.encode::<N>(&element_error), Err(CustomError::MistypedMember { member, .. }) if member == "delegates[1]"));
+    }
+
+    #[test]
+    fn test_member_type_json() {
+        let member_type: MemberType = serde_json::from_value(json!({ "element": { "struct": { "name": "S", "members": [{ "name": "a", "type": "u8" }] } }, "length": 3 })).unwrap();
+        assert_eq!(member_type.to_string(), "[S; 3]");
+        assert_eq!(serde_json::to_value(MemberType::from(LiteralType::Field)).unwrap(), json!("field"));
+    }
+
+    #[test]
+    fn test_schema_from_json() {
+        let schema: CredentialSchema = serde_json::from_value(json!({
+            "name": "Credentials",
+            "members": [
+                { "name": "issuer", "type": "address" },
+                { "name": "dob", "type": "u32", "required": false, "constraints": [{ "kind": "range", "min": "0", "max": null }] }
+            ]
+        })).unwrap();
+        assert_eq!(schema.members()[0], SchemaMember::required("issuer", LiteralType::Address));
+        assert!(!schema.members()[1].required);
+    }
+
+    #[test]
+    fn test_encode_text_members() {
+        let schema: CredentialSchema = serde_json::from_value(json!({
+            "name": "Passport",
+            "members": [
+                { "name": "issuer", "type": "address" },
+                { "name": "full_name", "type": { "text": { "chunks": 4 } } },
+                { "name": "birthplace", "type": { "text": "hash" }, "required": false }
+            ]
+        })).unwrap();
+        assert_eq!(schema.members()[1].member_type, MemberType::text(StringEncoding::Chunks(4)));
+        assert_eq!(schema.members()[1].member_type.to_string(), "[field; 4]");
+        assert_eq!(schema.members()[2].member_type.to_string(), "field");
+
+        let full_name = "Maria José Carreño Quiñones de la Santísima Trinidad";
+        let members = schema.encode::<N>(&json!({ "issuer": ISSUER, "full_name": full_name })).unwrap();
+        let chunks: Vec<String> = crate::strings::encode_string::<N>(full_name, 4).unwrap().iter().map(|f| f.to_string()).collect();
+        let birthplace = crate::strings::hash_string::<N>("").unwrap();
+
+        // The encoded struct hashes like the Leo struct { issuer: address, full_name: [field; 4], birthplace: field }
+        let expected = Value::<N>::from_str(&format!(
+            "{{ issuer: {ISSUER}, full_name: [{}], birthplace: {birthplace} }}",
+            chunks.join(", ")
+        )).unwrap();
+        let encoded = generate_message_with_addresses_and_fields(Credential { data: members }).unwrap();
+        assert_eq!(create_hash(encoded, HashAlgorithm::POSEIDON2).unwrap(), create_hash(expected, HashAlgorithm::POSEIDON2).unwrap());
+
+        let too_long = json!({ "issuer": ISSUER, "full_name": full_name.repeat(2) });
+        assert!(matches!(schema.encode::<N>(&too_long), Err(CustomError::ConstraintViolation { member, .. }) if member == "full_name"));
+    }
+}
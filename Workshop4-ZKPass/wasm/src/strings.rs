@@ -0,0 +1,188 @@
+use super::*;
+use crate::helpers::hash_to_field_psd2;
+
+/// Number of UTF-8 bytes packed into one chunk field. 31 bytes always stay below the field modulus.
+pub const BYTES_PER_FIELD: usize = 31;
+
+/// How a UTF-8 string member is encoded into fields.
+///
+/// Both encodings start from the same length-prefixed chunks: the byte length of the string
+/// as a field, followed by the bytes in groups of [`BYTES_PER_FIELD`], each read as a
+/// little-endian integer. The last chunk is zero padded.
+///
+/// In JSON the encoding is written as `{ "chunks": 4 }` or `"hash"`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StringEncoding {
+    /// A Leo `[field; N]` holding the length followed by `N - 1` chunks, padded with zero
+    /// fields, so up to `31 * (N - 1)` bytes fit.
+    Chunks(u32),
+    /// A single `field`: `Poseidon2::hash_to_field(chunks)`, where `chunks` is the Leo
+    /// `[field; K]` holding the length and the `K - 1` chunks the string needs, without padding.
+    Hash,
+}
+
+impl StringEncoding {
+    /// Returns the maximum number of UTF-8 bytes the encoding can hold, if bounded.
+    pub fn capacity(&self) -> Option<usize> {
+        match self {
+            Self::Chunks(length) => Some((*length as usize).saturating_sub(1) * BYTES_PER_FIELD),
+            Self::Hash => None,
+        }
+    }
+
+    /// Encodes `text` as a `[field; N]` array or a single field literal.
+    pub(crate) fn encode<N: NetworkNative>(&self, path: &str, text: &str) -> Result<Plaintext<N>, CustomError> {
+        match self {
+            Self::Chunks(length) => {
+                let capacity = self.capacity().unwrap_or_default();
+                if *length == 0 || text.len() > capacity {
+                    return Err(CustomError::ConstraintViolation {
+                        member: path.to_string(),
+                        reason: format!("{} bytes of text do not fit in [field; {}], which holds {} bytes", text.len(), length, capacity),
+                    });
+                }
+                let mut fields = string_chunks::<N>(text);
+                fields.resize(*length as usize, Field::<N>::zero());
+                Ok(Plaintext::Array(fields.into_iter().map(|field| Plaintext::from(Literal::Field(field))).collect(), Default::default()))
+            }
+            Self::Hash => {
+                let chunks = string_chunks::<N>(text).into_iter().map(|field| Plaintext::from(Literal::Field(field))).collect();
+                let hash = hash_to_field_psd2(Plaintext::Array(chunks, Default::default()))?;
+                Ok(Plaintext::from(Literal::Field(hash)))
+            }
+        }
+    }
+}
+
+/// Returns the length-prefixed chunks of `text` without padding.
+fn string_chunks<N: NetworkNative>(text: &str) -> Vec<Field<N>> {
+    use snarkvm_console::prelude::*;
+
+    let bytes = text.as_bytes();
+    let mut fields = Vec::with_capacity(1 + bytes.len().div_ceil(BYTES_PER_FIELD));
+    fields.push(Field::<N>::from_u128(bytes.len() as u128));
+    for chunk in bytes.chunks(BYTES_PER_FIELD) {
+        fields.push(Field::new(N::Field::from_bytes_le_mod_order(chunk)));
+    }
+    fields
+}
+
+/// Encodes `text` into exactly `length` fields, see [`StringEncoding::Chunks`].
+pub fn encode_string<N: NetworkNative>(text: &str, length: u32) -> Result<Vec<Field<N>>, CustomError> {
+    match StringEncoding::Chunks(length).encode::<N>("text", text)? {
+        Plaintext::Array(elements, _) => Ok(elements.into_iter()
+            .filter_map(|element| match element {
+                Plaintext::Literal(Literal::Field(field), _) => Some(field),
+                _ => None,
+            })
+            .collect()),
+        _ => Err(CustomError::parse("text", "Chunk encoding did not produce a field array")),
+    }
+}
+
+/// Hashes `text` into a single field, see [`StringEncoding::Hash`].
+pub fn hash_string<N: NetworkNative>(text: &str) -> Result<Field<N>, CustomError> {
+    match StringEncoding::Hash.encode::<N>("text", text)? {
+        Plaintext::Literal(Literal::Field(field), _) => Ok(field),
+        _ => Err(CustomError::parse("text", "Hash encoding did not produce a field")),
+    }
+}
+
+/// Decodes fields produced by [`encode_string`] back into the original text.
+///
+/// Rejects non-canonical encodings: chunks that overflow 31 bytes, non-zero padding and
+/// invalid UTF-8.
+pub fn decode_string<N: NetworkNative>(fields: &[Field<N>]) -> Result<String, CustomError> {
+    use snarkvm_console::prelude::*;
+
+    let invalid = |message: &str| CustomError::parse("fields", message);
+    let (length, chunks) = fields.split_first().ok_or_else(|| invalid("Expected a length prefix"))?;
+
+    let length_bytes = length.to_bytes_le().map_err(|e| CustomError::parse("fields[0]", e))?;
+    if length_bytes[8..].iter().any(|byte| *byte != 0) {
+        return Err(CustomError::parse("fields[0]", "Length does not fit in 64 bits"));
+    }
+    let length = u64::from_le_bytes(length_bytes[..8].try_into().unwrap_or_default()) as usize;
+    if length > chunks.len() * BYTES_PER_FIELD {
+        return Err(CustomError::IndexOutOfRange { index: length, len: chunks.len() * BYTES_PER_FIELD });
+    }
+
+    let mut bytes = Vec::with_capacity(chunks.len() * BYTES_PER_FIELD);
+    for (i, chunk) in chunks.iter().enumerate() {
+        let chunk_bytes = chunk.to_bytes_le().map_err(|e| CustomError::parse(format!("fields[{}]", i + 1), e))?;
+        if chunk_bytes[BYTES_PER_FIELD..].iter().any(|byte| *byte != 0) {
+            return Err(CustomError::parse(format!("fields[{}]", i + 1), "Chunk holds more than 31 bytes"));
+        }
+        bytes.extend_from_slice(&chunk_bytes[..BYTES_PER_FIELD]);
+    }
+    if bytes[length..].iter().any(|byte| *byte != 0) {
+        return Err(invalid("Padding after the text must be zero"));
+    }
+    bytes.truncate(length);
+
+    String::from_utf8(bytes).map_err(|e| invalid(&format!("Text is not valid UTF-8: {}", e)))
+}
+
+/// Returns the text and encoding of a `{ "type": "text", "value": "...", "encoding": ... }` value.
+pub(crate) fn text_annotation(value: &JsonValue) -> Option<(&JsonValue, &JsonValue)> {
+    let object = value.as_object()?;
+    if object.len() != 3 || object.get("type")?.as_str()? != "text" {
+        return None;
+    }
+    Some((object.get("value")?, object.get("encoding")?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Define the network type for the tests
+    type N = TestnetV0;
+
+    const LONG_NAME: &str = "Maria José Carreño Quiñones de la Santísima Trinidad, Honolulu, Hawaiʻi";
+
+    #[test]
+    fn test_encode_and_decode_long_string() {
+        assert!(LONG_NAME.len() > 16);
+        let fields = encode_string::<N>(LONG_NAME, 4).unwrap();
+        assert_eq!(fields.len(), 4);
+        assert_eq!(fields[0], Field::<N>::from_u128(LONG_NAME.len() as u128));
+        assert_eq!(decode_string::<N>(&fields).unwrap(), LONG_NAME);
+
+        // The short string occupies one chunk and is zero padded
+        let fields = encode_string::<N>("Jane", 3).unwrap();
+        assert_eq!(fields[1], Field::<N>::from_u128(u32::from_le_bytes(*b"Jane") as u128));
+        assert_eq!(fields[2], Field::<N>::zero());
+        assert_eq!(decode_string::<N>(&fields).unwrap(), "Jane");
+        assert_eq!(decode_string::<N>(&encode_string::<N>("", 1).unwrap()).unwrap(), "");
+    }
+
+    #[test]
+    fn test_encode_string_rejects_overflow() {
+        assert!(matches!(encode_string::<N>(LONG_NAME, 3), Err(CustomError::ConstraintViolation { .. })));
+        assert!(encode_string::<N>("", 0).is_err());
+
+        let mut fields = encode_string::<N>("Jane", 2).unwrap();
+        fields[0] = Field::<N>::from_u128(40);
+        assert!(decode_string::<N>(&fields).is_err());
+        fields[0] = Field::<N>::from_u128(2);
+        assert!(decode_string::<N>(&fields).is_err());
+    }
+
+    #[test]
+    fn test_hash_string_uses_length_prefixed_chunks() {
+        let hash = hash_string::<N>(LONG_NAME).unwrap();
+        let fields = encode_string::<N>(LONG_NAME, 4).unwrap();
+        let chunks = Value::<N>::from_str(&format!("[{}]", fields.iter().map(ToString::to_string).collect::<Vec<_>>().join(", "))).unwrap();
+        assert_eq!(hash, N::hash_psd2(&chunks.to_fields().unwrap()).unwrap());
+        assert_ne!(hash, N::hash_psd2(&fields).unwrap());
+        assert_ne!(hash, hash_string::<N>("Maria").unwrap());
+    }
+
+    #[test]
+    fn test_string_encoding_json() {
+        assert_eq!(serde_json::to_value(StringEncoding::Chunks(4)).unwrap(), serde_json::json!({ "chunks": 4 }));
+        assert_eq!(serde_json::from_value::<StringEncoding>(serde_json::json!("hash")).unwrap(), StringEncoding::Hash);
+    }
+}
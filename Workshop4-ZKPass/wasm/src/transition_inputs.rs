@@ -0,0 +1,308 @@
+use super::*;
+use snarkvm_console::program::LiteralType;
+
+
+/// The ZPass transitions that take a signed credential as input.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ZPassTransition {
+    /// `verify_poseidon2_zpass.aleo/issue(sig, pri: PrivateCredentials, pub: PublicCredentials)`
+    VerifyPoseidon2ZPassIssue = 0,
+    /// `zpass_hiding.aleo/issue(sig, pri: PrivateCredential, pub: PublicCredential)`
+    ZPassHidingIssue = 1,
+    /// `zpass_invalidate.aleo/issue(sig, pri: PrivateCredentials, pub: PublicCredentials)`
+    ZPassInvalidateIssue = 2,
+    /// `zpass_invalidate_hiding.aleo/issue(sig, pri: PrivateCredential, pub: PublicCredential)`
+    ZPassInvalidateHidingIssue = 3,
+    /// `verify_poseidon2.aleo/verify(sig, public issuer, dob, nationality, expiry)`
+    VerifyPoseidon2Verify = 4,
+    /// `zpass_merkle_8.aleo/issue(sig, leaves_hashes: [field; 8], issuer)`
+    ZPassMerkle8Issue = 5,
+}
+
+/// Whether a transition input is declared `public` or (implicitly) `private`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InputVisibility {
+    Private,
+    Public,
+}
+
+/// A single transition input rendered as an Aleo plaintext literal.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TransitionInput {
+    pub name: String,
+    pub value: String,
+    pub visibility: InputVisibility,
+}
+
+/// The ordered inputs of a transition call.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TransitionInputs {
+    pub program: String,
+    pub function: String,
+    pub inputs: Vec<TransitionInput>,
+}
+
+impl TransitionInputs {
+    /// Returns the input literals in order, as expected by `leo run` and the Aleo SDK.
+    pub fn values(&self) -> Vec<String> {
+        self.inputs.iter().map(|input| input.value.clone()).collect()
+    }
+}
+
+/// How the value of an input is taken from the credential data.
+#[derive(Debug, Clone, Copy)]
+enum InputKind {
+    /// The issuer signature.
+    Signature,
+    /// A struct built from the credential members named in the program's struct of that name.
+    Struct(&'static str),
+    /// The credential member with the input's name, typed by the struct or record of that name.
+    Member(&'static str),
+    /// A `[field; N]` array taken from the credential member with the input's name.
+    FieldArray(usize),
+}
+
+const STRUCT_ISSUE_INPUTS: [(&str, InputVisibility, InputKind); 3] = [
+    ("sig", InputVisibility::Private, InputKind::Signature),
+    ("pri", InputVisibility::Private, InputKind::Struct("PrivateCredentials")),
+    ("pub", InputVisibility::Public, InputKind::Struct("PublicCredentials")),
+];
+
+const HIDING_ISSUE_INPUTS: [(&str, InputVisibility, InputKind); 3] = [
+    ("sig", InputVisibility::Private, InputKind::Signature),
+    ("pri", InputVisibility::Private, InputKind::Struct("PrivateCredential")),
+    ("pub", InputVisibility::Public, InputKind::Struct("PublicCredential")),
+];
+
+const VERIFY_INPUTS: [(&str, InputVisibility, InputKind); 5] = [
+    ("sig", InputVisibility::Private, InputKind::Signature),
+    ("issuer", InputVisibility::Public, InputKind::Member("Credentials")),
+    ("dob", InputVisibility::Private, InputKind::Member("Credentials")),
+    ("nationality", InputVisibility::Private, InputKind::Member("Credentials")),
+    ("expiry", InputVisibility::Private, InputKind::Member("Credentials")),
+];
+
+const MERKLE_ISSUE_INPUTS: [(&str, InputVisibility, InputKind); 3] = [
+    ("sig", InputVisibility::Private, InputKind::Signature),
+    ("leaves_hashes", InputVisibility::Private, InputKind::FieldArray(8)),
+    ("issuer", InputVisibility::Private, InputKind::Member("ZPass")),
+];
+
+impl ZPassTransition {
+    /// The program the transition belongs to.
+    pub fn program(&self) -> ZPassProgram {
+        match self {
+            Self::VerifyPoseidon2ZPassIssue => ZPassProgram::VerifyPoseidon2ZPass,
+            Self::ZPassHidingIssue => ZPassProgram::ZPassHiding,
+            Self::ZPassInvalidateIssue => ZPassProgram::ZPassInvalidate,
+            Self::ZPassInvalidateHidingIssue => ZPassProgram::ZPassInvalidateHiding,
+            Self::VerifyPoseidon2Verify => ZPassProgram::VerifyPoseidon2,
+            Self::ZPassMerkle8Issue => ZPassProgram::ZPassMerkle8,
+        }
+    }
+
+    pub fn program_id(&self) -> &'static str {
+        self.program().program_id()
+    }
+
+    pub fn function_name(&self) -> &'static str {
+        match self {
+            Self::VerifyPoseidon2Verify => "verify",
+            _ => "issue",
+        }
+    }
+
+    fn inputs(&self) -> &'static [(&'static str, InputVisibility, InputKind)] {
+        match self {
+            Self::VerifyPoseidon2ZPassIssue | Self::ZPassInvalidateIssue => &STRUCT_ISSUE_INPUTS,
+            Self::ZPassHidingIssue | Self::ZPassInvalidateHidingIssue => &HIDING_ISSUE_INPUTS,
+            Self::VerifyPoseidon2Verify => &VERIFY_INPUTS,
+            Self::ZPassMerkle8Issue => &MERKLE_ISSUE_INPUTS,
+        }
+    }
+}
+
+impl FromStr for ZPassTransition {
+    type Err = CustomError;
+
+    /// Parses a `program.aleo/function` locator.
+    fn from_str(locator: &str) -> Result<Self, Self::Err> {
+        [
+            Self::VerifyPoseidon2ZPassIssue,
+            Self::ZPassHidingIssue,
+            Self::ZPassInvalidateIssue,
+            Self::ZPassInvalidateHidingIssue,
+            Self::VerifyPoseidon2Verify,
+            Self::ZPassMerkle8Issue,
+        ]
+        .into_iter()
+        .find(|transition| format!("{}/{}", transition.program_id(), transition.function_name()) == locator)
+        .ok_or_else(|| CustomError::parse(locator, "Unsupported transition"))
+    }
+}
+
+/// Builds the ordered inputs of `transition` from an issuer signature and the credential data.
+///
+/// For the struct-based `issue` transitions the data holds the `PrivateCredentials` members
+/// plus `salt`; for `verify_poseidon2.aleo/verify` the individual members; and for
+/// `zpass_merkle_8.aleo/issue` the `issuer` and the eight `leaves_hashes`.
+pub fn build_transition_inputs<N: NetworkNative>(transition: ZPassTransition, signature: &str, data: &JsonValue) -> Result<TransitionInputs, CustomError> {
+    let object = data.as_object()
+        .ok_or_else(|| CustomError::parse("data", "Credential data must be a JSON object"))?;
+    let schemas = schemas_from_leo(transition.program().program_source())?;
+    let schema = |name: &str| schemas.get(name)
+        .ok_or_else(|| CustomError::parse(name, format!("Not defined in {}", transition.program_id())));
+
+    let mut inputs = Vec::with_capacity(transition.inputs().len());
+    for (name, visibility, kind) in transition.inputs() {
+        let value = match kind {
+            InputKind::Signature => Signature::<N>::from_str(signature)
+                .map_err(|e| CustomError::parse("signature", e))?
+                .to_string(),
+            InputKind::Struct(struct_name) => {
+                let schema = schema(struct_name)?;
+                // Only the members of this struct are taken from the data, in schema order
+                let subset: serde_json::Map<String, JsonValue> = schema.members().iter()
+                    .filter_map(|member| object.get(&member.name).map(|value| (member.name.clone(), value.clone())))
+                    .collect();
+                struct_literal(&schema.encode::<N>(&JsonValue::Object(subset))?)
+            }
+            InputKind::Member(definition) => {
+                let member = schema(definition)?.members().iter()
+                    .find(|member| &member.name == name)
+                    .ok_or_else(|| CustomError::MissingMember(format!("{}.{}", definition, name)))?;
+                let value = object.get(*name)
+                    .ok_or_else(|| CustomError::MissingMember(name.to_string()))?;
+                let literal_type = member.member_type.literal_type()
+                    .ok_or_else(|| CustomError::UnsupportedType { member: format!("{}.{}", definition, name), ty: member.member_type.to_string() })?;
+                member_literal::<N>(name, value, literal_type)?.to_string()
+            }
+            InputKind::FieldArray(length) => {
+                let values = object.get(*name).and_then(JsonValue::as_array)
+                    .ok_or_else(|| CustomError::MissingMember(name.to_string()))?;
+                if values.len() != *length {
+                    return Err(CustomError::MistypedMember {
+                        member: name.to_string(),
+                        expected: format!("[field; {}]", length),
+                        found: format!("{} elements", values.len()),
+                    });
+                }
+                let fields = values.iter()
+                    .map(|value| member_literal::<N>(name, value, LiteralType::Field).map(|field| field.to_string()))
+                    .collect::<Result<Vec<_>, _>>()?;
+                format!("[{}]", fields.join(", "))
+            }
+        };
+        inputs.push(TransitionInput { name: name.to_string(), value, visibility: *visibility });
+    }
+
+    Ok(TransitionInputs {
+        program: transition.program_id().to_string(),
+        function: transition.function_name().to_string(),
+        inputs,
+    })
+}
+
+fn member_literal<N: NetworkNative>(name: &str, value: &JsonValue, literal_type: LiteralType) -> Result<Literal<N>, CustomError> {
+    schema::parse_json_literal::<N>(value, literal_type)
+        .ok_or_else(|| CustomError::MistypedMember {
+            member: name.to_string(),
+            expected: literal_type.to_string(),
+            found: value.to_string(),
+        })
+}
+
+/// Renders encoded members as a single-line Aleo struct literal.
+pub(crate) fn struct_literal<N: NetworkNative>(members: &IndexMap<String, Plaintext<N>>) -> String {
+    let members: Vec<String> = members.iter().map(|(name, value)| format!("{}: {}", name, value)).collect();
+    format!("{{ {} }}", members.join(", "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    // Define the network type for the tests
+    type N = TestnetV0;
+
+    const PRIVATE_KEY: &str = "APrivateKey1zkp5LqRmm7535XfiX77VPQEgsS2Dj1B2DvH4QNP1UYrHEoR";
+    const ISSUER: &str = "aleo1ekyuzclmcw3aj7qncsxxaapxem82mgrd8zadgrrvl5k705zx6q9s7usuqy";
+    const SUBJECT: &str = "aleo14w44zfrehup9g894j7tgeyz5gsjuxn0nfn09vd2fvpznrg85rs8skywkte";
+
+    fn credential_data() -> JsonValue {
+        json!({
+            "issuer": ISSUER,
+            "subject": SUBJECT,
+            "dob": "20000101u32",
+            "nationality": "123field",
+            "expiry": "20300101u32"
+        })
+    }
+
+    fn signature() -> String {
+        let message = SignInboundMessage::from_json(credential_data());
+        sign_message_with_logger::<N>(PRIVATE_KEY.to_string(), message, HashAlgorithm::POSEIDON2, &StdoutLogger).unwrap().0
+    }
+
+    #[test]
+    fn test_issue_inputs_from_issued_credential() {
+        let message = SignInboundMessage::from_json(credential_data());
+        let issued = issuance::issue_credential::<N>(PRIVATE_KEY.to_string(), message, HashAlgorithm::POSEIDON2, SaltSource::Random, &StdoutLogger).unwrap();
+
+        let mut data = credential_data();
+        data["salt"] = json!(issued.salt());
+        let inputs = build_transition_inputs::<N>(ZPassTransition::VerifyPoseidon2ZPassIssue, issued.signature(), &data).unwrap();
+
+        assert_eq!(inputs.program, "verify_poseidon2_zpass.aleo");
+        assert_eq!(inputs.function, "issue");
+        assert_eq!(inputs.values(), vec![
+            issued.signature().to_string(),
+            format!("{{ issuer: {ISSUER}, subject: {SUBJECT}, dob: 20000101u32, nationality: 123field, expiry: 20300101u32 }}"),
+            issued.public_credentials().to_string(),
+        ]);
+        assert_eq!(inputs.inputs[2].visibility, InputVisibility::Public);
+
+        // Every rendered input must be a valid Aleo value
+        for value in inputs.values() {
+            assert!(Value::<N>::from_str(&value).is_ok(), "{} is not a valid value", value);
+        }
+    }
+
+    #[test]
+    fn test_verify_inputs() {
+        let signature = signature();
+        let inputs = build_transition_inputs::<N>("verify_poseidon2.aleo/verify".parse().unwrap(), &signature, &credential_data()).unwrap();
+        assert_eq!(inputs.values(), vec![signature, ISSUER.to_string(), "20000101u32".to_string(), "123field".to_string(), "20300101u32".to_string()]);
+        let visibilities: Vec<InputVisibility> = inputs.inputs.iter().map(|input| input.visibility).collect();
+        assert_eq!(visibilities[..2], [InputVisibility::Private, InputVisibility::Public]);
+    }
+
+    #[test]
+    fn test_merkle_issue_inputs() {
+        let leaves = hash_inputs_size_8::<N>(vec![ISSUER, "20000101u32", "123field"]).unwrap();
+        let root = MerkleTree::<N>::new(leaves.clone()).unwrap().root();
+        let signature = sign_root::<N>(PRIVATE_KEY, &root.to_string()).unwrap();
+        let data = json!({
+            "issuer": ISSUER,
+            "leaves_hashes": leaves.iter().map(|leaf| leaf.to_string()).collect::<Vec<_>>()
+        });
+
+        let inputs = build_transition_inputs::<N>(ZPassTransition::ZPassMerkle8Issue, &signature, &data).unwrap();
+        assert_eq!(inputs.values()[2], ISSUER);
+        assert!(Value::<N>::from_str(&inputs.values()[1]).is_ok());
+
+        let short = json!({ "issuer": ISSUER, "leaves_hashes": ["1field"] });
+        assert!(build_transition_inputs::<N>(ZPassTransition::ZPassMerkle8Issue, &signature, &short).is_err());
+    }
+
+    #[test]
+    fn test_invalid_inputs() {
+        let signature = signature();
+        assert!(build_transition_inputs::<N>(ZPassTransition::ZPassHidingIssue, &signature, &credential_data()).is_err(), "salt is missing");
+        assert!(build_transition_inputs::<N>(ZPassTransition::VerifyPoseidon2Verify, "sign1invalid", &credential_data()).is_err());
+        assert!("unknown.aleo/issue".parse::<ZPassTransition>().is_err());
+    }
+}
@@ -0,0 +1,213 @@
+use super::*;
+use crate::schema::{parse_json_literal, struct_plaintext, typed_annotation};
+use crate::strings::{text_annotation, StringEncoding};
+
+/// Type suffixes of the numeric Aleo literals.
+const NUMERIC_SUFFIXES: [&str; 13] = [
+    "field", "group", "scalar", "u8", "u16", "u32", "u64", "u128", "i8", "i16", "i32", "i64", "i128",
+];
+
+/// How untyped credential strings are turned into Aleo literals.
+///
+/// Shared by the credential signer and the merkle leaf hasher so that the same input
+/// always encodes to the same value.
+#[wasm_bindgen]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ParseMode {
+    /// Aleo literals are parsed as such and any other text is encoded as a field,
+    /// e.g. `"Hawaiiu8"` becomes the field of its UTF-8 bytes.
+    #[default]
+    Lenient = 0,
+    /// Every string must be a complete Aleo literal with its type suffix (`"20000101u32"`).
+    Strict = 1,
+}
+
+/// Parses a credential string into an Aleo literal.
+///
+/// Strings that look like literals (an `aleo1` prefix, or a number with a type suffix) but
+/// fail to parse are always rejected rather than silently encoded as text, and so are field,
+/// scalar and group values outside the modulus.
+pub fn parse_literal<N: NetworkNative>(path: &str, text: &str, mode: ParseMode) -> Result<Literal<N>, CustomError> {
+    let error = match Literal::<N>::from_str(text) {
+        Ok(literal) if is_canonical(text, &literal) => return Ok(literal),
+        Ok(literal) => return Err(CustomError::parse(path, format!("`{}` is outside the {} modulus", text, literal.to_type()))),
+        Err(error) => error,
+    };
+
+    match mode {
+        ParseMode::Strict => Err(CustomError::parse(path, format!(
+            "Expected an Aleo literal with a type suffix such as `20000101u32`, or a typed value {{ \"type\", \"value\" }}: {}",
+            error
+        ))),
+        ParseMode::Lenient if looks_like_literal(text) => Err(CustomError::parse(path, error)),
+        ParseMode::Lenient => string_to_field::<N>(Some(text.to_string()))
+            .map(Literal::Field)
+            .map_err(|e| CustomError::parse(path, e)),
+    }
+}
+
+/// Returns whether `text` spells the parsed field, scalar or group value in canonical form.
+///
+/// snarkVM reduces these literals modulo the field order while parsing, so `p + 1` would
+/// otherwise be accepted as `1field`. Negative values are rejected for the same reason.
+pub(crate) fn is_canonical<N: NetworkNative>(text: &str, literal: &Literal<N>) -> bool {
+    if !matches!(literal, Literal::Field(_) | Literal::Scalar(_) | Literal::Group(_)) {
+        return true;
+    }
+    let literal_type = literal.to_type();
+    let type_name = literal_type.type_name();
+    let digits = text.trim_end_matches(type_name).replace('_', "");
+    let digits = digits.trim_start_matches('0');
+    let canonical = literal.to_string();
+    let canonical = canonical.trim_end_matches(type_name);
+    digits == canonical || (digits.is_empty() && canonical == "0")
+}
+
+fn looks_like_literal(text: &str) -> bool {
+    text.starts_with("aleo1")
+        || (text.starts_with(|c: char| c.is_ascii_digit() || c == '-') && NUMERIC_SUFFIXES.iter().any(|suffix| text.ends_with(suffix)))
+}
+
+/// Parses a JSON credential value into a plaintext.
+///
+/// Strings go through [`parse_literal`], booleans become `Boolean`, objects become nested
+/// structs and arrays become `Plaintext::Array`. Numbers carry no Leo type of their own and
+/// must be annotated as `{ "type": "u32", "value": 20000101 }`, or signed with a schema.
+/// Free text of any length is annotated as `{ "type": "text", "value": "...", "encoding": { "chunks": 4 } }`,
+/// see [`StringEncoding`]. Object members are sorted by key, whatever order the JSON has.
+pub fn parse_value<N: NetworkNative>(path: &str, value: &JsonValue, mode: ParseMode) -> Result<Plaintext<N>, CustomError> {
+    match value {
+        JsonValue::String(s) => parse_literal::<N>(path, s, mode).map(Plaintext::from),
+        JsonValue::Bool(b) => Ok(Plaintext::from(Literal::Boolean(Boolean::new(*b)))),
+        JsonValue::Number(_) => Err(CustomError::parse(path, format!(
+            "JSON number without a type, annotate it as {{ \"type\": \"u32\", \"value\": {} }} or sign with a schema",
+            value
+        ))),
+        JsonValue::Object(object) => {
+            if let Some((text, encoding)) = text_annotation(value) {
+                return parse_text(path, value, text, encoding);
+            }
+            match typed_annotation(value) {
+                Some((literal_type, inner)) => parse_json_literal::<N>(inner, literal_type)
+                    .map(Plaintext::from)
+                    .ok_or_else(|| CustomError::MistypedMember {
+                        member: path.to_string(),
+                        expected: literal_type.to_string(),
+                        found: inner.to_string(),
+                    }),
+                None => {
+                    let mut members = IndexMap::with_capacity(object.len());
+                    for (key, value) in object {
+                        members.insert(key.clone(), parse_value(&format!("{}.{}", path, key), value, mode)?);
+                    }
+                    members.sort_keys();
+                    struct_plaintext(members)
+                }
+            }
+        }
+        JsonValue::Array(items) => {
+            let elements = items.iter().enumerate()
+                .map(|(i, item)| parse_value(&format!("{}[{}]", path, i), item, mode))
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(Plaintext::Array(elements, Default::default()))
+        }
+        JsonValue::Null => Err(CustomError::parse(path, "Member is null")),
+    }
+}
+
+/// Encodes a `{ "type": "text", "value", "encoding" }` value with its string encoding.
+fn parse_text<N: NetworkNative>(path: &str, value: &JsonValue, text: &JsonValue, encoding: &JsonValue) -> Result<Plaintext<N>, CustomError> {
+    let mistyped = || CustomError::MistypedMember {
+        member: path.to_string(),
+        expected: "text".to_string(),
+        found: value.to_string(),
+    };
+    let encoding: StringEncoding = serde_json::from_value(encoding.clone()).map_err(|_| mistyped())?;
+    encoding.encode::<N>(path, text.as_str().ok_or_else(mistyped)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    // Define the network type for the tests
+    type N = TestnetV0;
+
+    #[test]
+    fn test_parse_literal_modes() {
+        assert_eq!(parse_literal::<N>("dob", "20000101u32", ParseMode::Strict).unwrap().to_string(), "20000101u32");
+        assert_eq!(parse_literal::<N>("flag", "true", ParseMode::Strict).unwrap().to_string(), "true");
+
+        // Text that merely ends in a type suffix is not a literal
+        let hawaii = parse_literal::<N>("nationality", "Hawaiiu8", ParseMode::Lenient).unwrap();
+        assert_eq!(hawaii, Literal::Field(string_to_field::<N>(Some("Hawaiiu8".to_string())).unwrap()));
+        assert!(matches!(parse_literal::<N>("nationality", "Hawaiiu8", ParseMode::Strict), Err(CustomError::Parse { .. })));
+        assert!(parse_literal::<N>("name", "myfield", ParseMode::Strict).is_err());
+        assert!(parse_literal::<N>("count", "123", ParseMode::Strict).is_err());
+        assert_eq!(parse_literal::<N>("count", "123", ParseMode::Lenient).unwrap().to_string(), "123field");
+
+        // Malformed literals are rejected in both modes
+        assert!(parse_literal::<N>("level", "70000u16", ParseMode::Lenient).is_err());
+        assert!(parse_literal::<N>("issuer", "aleo1invalid", ParseMode::Lenient).is_err());
+    }
+
+    #[test]
+    fn test_parse_full_range_literals() {
+        // A merkle root is a full-width field element
+        let leaves = hash_inputs_size_8::<N>(vec!["20000101u32", "123field"]).unwrap();
+        let root = MerkleTree::<N>::new(leaves).unwrap().root().to_string();
+        assert_eq!(parse_literal::<N>("root", &root, ParseMode::Strict).unwrap().to_string(), root);
+
+        let modulus_minus_one = "8444461749428370424248824938781546531375899335154063827935233455917409239040field";
+        assert_eq!(parse_literal::<N>("value", modulus_minus_one, ParseMode::Strict).unwrap().to_string(), modulus_minus_one);
+        assert_eq!(parse_literal::<N>("value", "1_000field", ParseMode::Strict).unwrap().to_string(), "1000field");
+        assert_eq!(parse_literal::<N>("value", "0group", ParseMode::Strict).unwrap().to_string(), "0group");
+
+        // Values outside the modulus are errors in both modes rather than reduced or re-encoded
+        for text in [
+            "8444461749428370424248824938781546531375899335154063827935233455917409239041field",
+            "99999999999999999999999999999999999999999999999999999999999999999999999999999999field",
+            "2111115437357092606062206234695386632838870926408408195193685246394721360383scalar",
+            "-1field",
+        ] {
+            assert!(matches!(parse_literal::<N>("value", text, ParseMode::Lenient), Err(CustomError::Parse { .. })), "{}", text);
+            assert!(parse_literal::<N>("value", text, ParseMode::Strict).is_err(), "{}", text);
+        }
+    }
+
+    #[test]
+    fn test_parse_value_with_typed_annotations() {
+        let value = json!([{ "type": "u32", "value": 20000101 }, { "type": "field", "value": "7" }, "1u8"]);
+        let plaintext = parse_value::<N>("values", &value, ParseMode::Strict).unwrap();
+        assert_eq!(plaintext.to_string(), "[\n  20000101u32,\n  7field,\n  1u8\n]");
+        assert!(matches!(
+            parse_value::<N>("dob", &json!({ "type": "u8", "value": "20000101" }), ParseMode::Strict),
+            Err(CustomError::MistypedMember { .. })
+        ));
+    }
+
+    #[test]
+    fn test_parse_value_with_text_annotation() {
+        let name = "Maria José Carreño Quiñones";
+        let value = json!({ "type": "text", "value": name, "encoding": { "chunks": 3 } });
+        let Plaintext::Array(elements, _) = parse_value::<N>("name", &value, ParseMode::Strict).unwrap() else {
+            panic!("Expected a field array");
+        };
+        assert_eq!(elements.len(), 3);
+
+        let hashed = json!({ "type": "text", "value": name, "encoding": "hash" });
+        let expected = Literal::Field(crate::strings::hash_string::<N>(name).unwrap());
+        assert_eq!(parse_value::<N>("name", &hashed, ParseMode::Strict).unwrap(), Plaintext::from(expected));
+
+        let invalid = json!({ "type": "text", "value": name, "encoding": "base64" });
+        assert!(matches!(parse_value::<N>("name", &invalid, ParseMode::Strict), Err(CustomError::MistypedMember { .. })));
+    }
+
+    #[test]
+    fn test_parse_mode_json() {
+        assert_eq!(serde_json::to_value(ParseMode::Strict).unwrap(), json!("strict"));
+        assert_eq!(serde_json::from_value::<ParseMode>(json!("lenient")).unwrap(), ParseMode::Lenient);
+    }
+}
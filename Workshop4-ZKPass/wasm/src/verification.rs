@@ -0,0 +1,229 @@
+use super::*;
+use crate::records::{PlainZPass, ZPassRecord};
+use snarkvm_console::program::Record;
+
+/// Outcome of verifying a credential against its issuer signature.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VerificationStatus {
+    /// The signature over the rebuilt credential hash was made by the expected issuer.
+    Valid = 0,
+    /// The hash rebuilt from the data differs from the hash the caller expected.
+    HashMismatch = 1,
+    /// The signature was made by the expected issuer, but not over this credential.
+    BadSignature = 2,
+    /// The signature, or the `issuer` member of the data, belongs to a different address.
+    WrongIssuer = 3,
+}
+
+/// Detailed result of [`verify_credential`].
+#[wasm_bindgen]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CredentialVerification {
+    pub(crate) status: VerificationStatus,
+    pub(crate) hash: String,
+    pub(crate) signer: String,
+}
+
+impl CredentialVerification {
+    pub fn status(&self) -> VerificationStatus {
+        self.status
+    }
+
+    /// The hash rebuilt from the credential data.
+    pub fn hash(&self) -> &str {
+        &self.hash
+    }
+
+    /// The address whose key produced the signature.
+    pub fn signer(&self) -> &str {
+        &self.signer
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.status == VerificationStatus::Valid
+    }
+}
+
+/// Verifies a credential end to end from its data.
+///
+/// The data is encoded and hashed exactly like [`sign_message_with_logger`] does, then the
+/// signature is checked against `issuer`. Pass the hash returned at signing time as
+/// `expected_hash` to tell a tampered credential (`HashMismatch`) apart from a signature over
+/// something else (`BadSignature`).
+///
+/// Malformed inputs are errors; a well-formed credential that does not verify is reported
+/// through [`CredentialVerification::status`].
+pub fn verify_credential<N: NetworkNative>(
+    message: &SignInboundMessage,
+    hash: HashAlgorithm,
+    signature: &str,
+    issuer: &str,
+    expected_hash: Option<&str>,
+) -> Result<CredentialVerification, CustomError> {
+    let issuer = Address::<N>::from_str(issuer)
+        .map_err(|e| CustomError::parse("issuer", e))?;
+    verify_credential_with_issuer::<N>(message, hash, signature, &issuer, expected_hash)
+}
+
+/// Verifies a credential like [`verify_credential`] against an already parsed issuer address.
+pub(crate) fn verify_credential_with_issuer<N: NetworkNative>(
+    message: &SignInboundMessage,
+    hash: HashAlgorithm,
+    signature: &str,
+    issuer: &Address<N>,
+    expected_hash: Option<&str>,
+) -> Result<CredentialVerification, CustomError> {
+    let signature = Signature::<N>::from_str(signature)
+        .map_err(|e| CustomError::parse("signature", e))?;
+
+    let hash = credential_hash(&credential_message::<N>(message)?, message, hash)?;
+    let signer = signature.to_address();
+    let result = |status| CredentialVerification { status, hash: hash.clone(), signer: signer.to_string() };
+
+    if let Some(expected_hash) = expected_hash {
+        let expected_hash = Field::<N>::from_str(expected_hash)
+            .map_err(|e| CustomError::parse("expected_hash", e))?;
+        if expected_hash.to_string() != hash {
+            return Ok(result(VerificationStatus::HashMismatch));
+        }
+    }
+
+    // The issuer named in the data must be the one that signed it
+    let data_issuer = message.data.get("issuer").and_then(JsonValue::as_str);
+    if &signer != issuer || data_issuer.is_some_and(|data_issuer| data_issuer != issuer.to_string()) {
+        return Ok(result(VerificationStatus::WrongIssuer));
+    }
+
+    let hash_fields = string_to_value_fields::<N>(&hash)?;
+    match verify_signature_with_address_and_message(&signature, issuer, &hash_fields) {
+        true => Ok(result(VerificationStatus::Valid)),
+        false => Ok(result(VerificationStatus::BadSignature)),
+    }
+}
+
+/// Verifies a decrypted `verify_poseidon2_zpass.aleo/ZPass` record against the issuer signature,
+/// without a chain.
+///
+/// `FullCredentials` is rebuilt from the record members with `subject = owner`, which `issue`
+/// enforces through `self.caller`, and hashed with Poseidon2 as `issue` does before it checks
+/// the signature against `issuer`.
+pub fn verify_zpass<N: NetworkNative>(zpass: &PlainZPass<N>, signature: &str) -> Result<CredentialVerification, CustomError> {
+    let mut data = serde_json::Map::with_capacity(6);
+    data.insert("issuer".to_string(), JsonValue::String(zpass.issuer.to_string()));
+    data.insert("subject".to_string(), JsonValue::String(zpass.owner.to_string()));
+    data.insert("dob".to_string(), JsonValue::String(zpass.dob.to_string()));
+    data.insert("nationality".to_string(), JsonValue::String(zpass.nationality.to_string()));
+    data.insert("expiry".to_string(), JsonValue::String(zpass.expiry.to_string()));
+    data.insert("salt".to_string(), JsonValue::String(zpass.salt.to_string()));
+
+    let schema = ZPassProgram::VerifyPoseidon2ZPass.schema("FullCredentials")?;
+    let message = SignInboundMessage::from_json(JsonValue::Object(data))
+        .with_mode(ParseMode::Strict)
+        .with_credential_schema(schema);
+    verify_credential_with_issuer::<N>(&message, HashAlgorithm::POSEIDON2, signature, &zpass.issuer, None)
+}
+
+/// Parses a decrypted `verify_poseidon2_zpass.aleo/ZPass` record plaintext and verifies it, see
+/// [`verify_zpass`].
+pub fn verify_zpass_record<N: NetworkNative>(record: &str, signature: &str) -> Result<CredentialVerification, CustomError> {
+    let record = Record::<N, Plaintext<N>>::from_str(record.trim())
+        .map_err(|e| CustomError::parse("record", e))?;
+    let zpass = ZPassRecord::from_record(ZPassProgram::VerifyPoseidon2ZPass, &record)?.zpass.into_plain()?;
+    verify_zpass(&zpass, signature)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    // Define the network type for the tests
+    type N = TestnetV0;
+
+    const PRIVATE_KEY: &str = "APrivateKey1zkp5LqRmm7535XfiX77VPQEgsS2Dj1B2DvH4QNP1UYrHEoR";
+    const ISSUER: &str = "aleo1ekyuzclmcw3aj7qncsxxaapxem82mgrd8zadgrrvl5k705zx6q9s7usuqy";
+    const OTHER_KEY: &str = "APrivateKey1zkp8CZNn3yeCseEtxuVPbDCwSyhGW6yZKUYKfgXmcpoGPWH";
+    const SUBJECT: &str = "aleo14w44zfrehup9g894j7tgeyz5gsjuxn0nfn09vd2fvpznrg85rs8skywkte";
+
+    fn credential(dob: &str) -> SignInboundMessage {
+        SignInboundMessage::from_json(json!({ "issuer": ISSUER, "subject": SUBJECT, "dob": dob, "nationality": "123field" })).with_mode(ParseMode::Strict)
+    }
+
+    fn sign(private_key: &str, message: SignInboundMessage) -> (String, String) {
+        sign_message_with_logger::<N>(private_key.to_string(), message, HashAlgorithm::POSEIDON2, &StdoutLogger).unwrap()
+    }
+
+    #[test]
+    fn test_verify_credential_statuses() {
+        let (signature, hash) = sign(PRIVATE_KEY, credential("20000101u32"));
+
+        let valid = verify_credential::<N>(&credential("20000101u32"), HashAlgorithm::POSEIDON2, &signature, ISSUER, Some(&hash)).unwrap();
+        assert!(valid.is_valid());
+        assert_eq!(valid.hash(), hash);
+        assert_eq!(valid.signer(), ISSUER);
+
+        // Tampered data is caught by the expected hash, or by the signature without one
+        let tampered = credential("19990101u32");
+        let mismatch = verify_credential::<N>(&tampered, HashAlgorithm::POSEIDON2, &signature, ISSUER, Some(&hash)).unwrap();
+        assert_eq!(mismatch.status(), VerificationStatus::HashMismatch);
+        let bad = verify_credential::<N>(&tampered, HashAlgorithm::POSEIDON2, &signature, ISSUER, None).unwrap();
+        assert_eq!(bad.status(), VerificationStatus::BadSignature);
+
+        // Hashing with another algorithm is a different message
+        let other_algorithm = verify_credential::<N>(&credential("20000101u32"), HashAlgorithm::BHP1024, &signature, ISSUER, None).unwrap();
+        assert_eq!(other_algorithm.status(), VerificationStatus::BadSignature);
+    }
+
+    #[test]
+    fn test_verify_credential_wrong_issuer() {
+        // Signed by a key that does not belong to the issuer named in the data
+        let (signature, _) = sign(OTHER_KEY, credential("20000101u32"));
+        let result = verify_credential::<N>(&credential("20000101u32"), HashAlgorithm::POSEIDON2, &signature, ISSUER, None).unwrap();
+        assert_eq!(result.status(), VerificationStatus::WrongIssuer);
+        assert_ne!(result.signer(), ISSUER);
+
+        // Verified against an issuer other than the one named in the data
+        let signer = result.signer().to_string();
+        let result = verify_credential::<N>(&credential("20000101u32"), HashAlgorithm::POSEIDON2, &signature, &signer, None).unwrap();
+        assert_eq!(result.status(), VerificationStatus::WrongIssuer);
+    }
+
+    #[test]
+    fn test_verify_credential_rejects_malformed_input() {
+        let (signature, _) = sign(PRIVATE_KEY, credential("20000101u32"));
+        let result = verify_credential::<N>(&credential("20000101u32"), HashAlgorithm::POSEIDON2, "sign1invalid", ISSUER, None);
+        assert_eq!(result.unwrap_err().path(), Some("signature"));
+        let result = verify_credential::<N>(&credential("20000101u32"), HashAlgorithm::POSEIDON2, &signature, "aleo1invalid", None);
+        assert_eq!(result.unwrap_err().path(), Some("issuer"));
+        let result = verify_credential::<N>(&credential("tomorrow"), HashAlgorithm::POSEIDON2, &signature, ISSUER, None);
+        assert_eq!(result.unwrap_err().code(), "PARSE_ERROR");
+    }
+
+    #[test]
+    fn test_verify_zpass_record() {
+        // The issuer signs `FullCredentials` for the subject, who issues the record to itself
+        let subject_key = PrivateKey::<N>::from_str(OTHER_KEY).unwrap();
+        let subject = Address::try_from(&subject_key).unwrap();
+        let schema = ZPassProgram::VerifyPoseidon2ZPass.schema("FullCredentials").unwrap();
+        let data = json!({ "issuer": ISSUER, "subject": subject.to_string(), "dob": "20000101u32", "nationality": "123field", "expiry": "20300101u32", "salt": "42scalar" });
+        let (signature, hash) = sign(PRIVATE_KEY, SignInboundMessage::from_json(data).with_credential_schema(schema).with_mode(ParseMode::Strict));
+
+        let record = |dob: &str| format!("{{ owner: {subject}.private, issuer: {ISSUER}.private, dob: {dob}.private, nationality: 123field.private, expiry: 20300101u32.private, salt: 42scalar.private, _nonce: 0group.public }}");
+        let verdict = verify_zpass_record::<N>(&record("20000101u32"), &signature).unwrap();
+        assert!(verdict.is_valid());
+        assert_eq!(verdict.hash(), hash);
+
+        // A record with altered attributes, or held by someone else, does not verify
+        assert_eq!(verify_zpass_record::<N>(&record("19990101u32"), &signature).unwrap().status(), VerificationStatus::BadSignature);
+        let mut zpass = ZPassRecord::<N>::from_record(ZPassProgram::VerifyPoseidon2ZPass, &Record::from_str(&record("20000101u32")).unwrap()).unwrap().zpass.into_plain().unwrap();
+        zpass.owner = Address::from_str(SUBJECT).unwrap();
+        assert_eq!(verify_zpass::<N>(&zpass, &signature).unwrap().status(), VerificationStatus::BadSignature);
+
+        // The signature must come from the record issuer
+        zpass.issuer = Address::from_str(SUBJECT).unwrap();
+        assert_eq!(verify_zpass::<N>(&zpass, &signature).unwrap().status(), VerificationStatus::WrongIssuer);
+        assert!(matches!(verify_zpass_record::<N>("{ owner: aleo1invalid }", &signature), Err(CustomError::Parse { .. })));
+    }
+}
@@ -1,5 +1,8 @@
 use super::*;
 use crate::merkle_tree::*;
+use crate::transition_inputs::struct_literal;
+use crate::issuance::{derive_salt as derive_salt_native, generate_salt as generate_salt_native};
+use zeroize::Zeroize;
 
 #[wasm_bindgen]
 #[derive(Debug, Clone, Copy)]
@@ -9,87 +12,280 @@ pub enum Network {
 }
 
 #[wasm_bindgen]
-pub fn hash_to_fields_size_8(inputs: Vec<String>, network: Network) -> Result<Vec<String>, String> {
-    match network {
+pub fn hash_to_fields_size_8(inputs: Vec<String>, network: Network) -> Result<Vec<String>, JsValue> {
+    let fields = match network {
         Network::Testnet => hash_inputs_size_8::<TestnetV0>(inputs.iter().map(|s| s.as_str()).collect())
-            .map(|fields| fields.iter().map(|f| f.to_string()).collect())
-            .map_err(|e| e.to_string()),
+            .map(|fields| fields.iter().map(|f| f.to_string()).collect()),
         Network::Mainnet => hash_inputs_size_8::<MainnetV0>(inputs.iter().map(|s| s.as_str()).collect())
-            .map(|fields| fields.iter().map(|f| f.to_string()).collect())
-            .map_err(|e| e.to_string()),
+            .map(|fields| fields.iter().map(|f| f.to_string()).collect()),
+    };
+    Ok(fields?)
+}
+
+/// Hashes up to eight credential values into merkle leaves, parsing them like signed credential data.
+/// `values` is an array of Aleo literal strings, booleans or `{ type, value }` objects.
+#[wasm_bindgen]
+pub fn hash_values_to_fields_size_8(values: JsValue, mode: ParseMode, network: Network) -> Result<Vec<String>, JsValue> {
+    let values: Vec<JsonValue> = from_js_value(values, "values")?;
+    let fields = match network {
+        Network::Testnet => hash_values_size_8::<TestnetV0>(&values, mode).map(|fields| fields.iter().map(|f| f.to_string()).collect()),
+        Network::Mainnet => hash_values_size_8::<MainnetV0>(&values, mode).map(|fields| fields.iter().map(|f| f.to_string()).collect()),
+    };
+    Ok(fields?)
+}
+
+#[wasm_bindgen]
+pub fn sign_merkle_root(private_key: String, root: String, network: Network) -> Result<String, JsValue> {
+    let signature = match network {
+        Network::Testnet => sign_root::<TestnetV0>(&private_key, &root),
+        Network::Mainnet => sign_root::<MainnetV0>(&private_key, &root),
+    };
+    Ok(signature?)
+}
+
+fn merkle_tree_from_inputs<N: NetworkNative>(inputs: &[String]) -> Result<MerkleTree<N>, CustomError> {
+    let fields = hash_inputs_size_8::<N>(inputs.iter().map(|s| s.as_str()).collect())?;
+    MerkleTree::<N>::new(fields)
+}
+
+fn fields_to_strings<N: NetworkNative>(fields: &[Field<N>]) -> Vec<String> {
+    fields.iter().map(|f| f.to_string()).collect()
+}
+
+/// Returns the proof of `inputs[index]` in the fixed eight-leaf tree of `zpass_merkle_8.aleo`.
+///
+/// @deprecated The sum hasher of this tree does not bind leaves to their positions. Use
+/// `get_credential_merkle_proof`, and keep this only for roots checked by `zpass_merkle_8.aleo`.
+#[wasm_bindgen]
+pub fn get_merkle_proof(inputs: Vec<String>, index: usize, network: Network) -> Result<Vec<String>, JsValue> {
+    let proof = match network {
+        Network::Testnet => merkle_tree_from_inputs::<TestnetV0>(&inputs)?.get_proof(index).map(|proof| fields_to_strings(&proof)),
+        Network::Mainnet => merkle_tree_from_inputs::<MainnetV0>(&inputs)?.get_proof(index).map(|proof| fields_to_strings(&proof)),
+    };
+    Ok(proof?)
+}
+
+/// Returns the root of the fixed eight-leaf tree of `zpass_merkle_8.aleo`.
+///
+/// @deprecated The sum hasher of this tree does not bind leaves to their positions. Use
+/// `get_credential_merkle_root`, and keep this only for roots checked by `zpass_merkle_8.aleo`.
+#[wasm_bindgen]
+pub fn get_merkle_root(inputs: Vec<String>, network: Network) -> Result<String, JsValue> {
+    let root = match network {
+        Network::Testnet => merkle_tree_from_inputs::<TestnetV0>(&inputs)?.root().to_string(),
+        Network::Mainnet => merkle_tree_from_inputs::<MainnetV0>(&inputs)?.root().to_string(),
+    };
+    Ok(root)
+}
+
+#[wasm_bindgen]
+pub fn get_merkle_tree(inputs: Vec<String>, network: Network) -> Result<JsValue, JsValue> {
+    let result: Vec<Vec<String>> = match network {
+        Network::Testnet => merkle_tree_from_inputs::<TestnetV0>(&inputs)?.levels().iter().map(|level| fields_to_strings(level)).collect(),
+        Network::Mainnet => merkle_tree_from_inputs::<MainnetV0>(&inputs)?.levels().iter().map(|level| fields_to_strings(level)).collect(),
+    };
+    to_js_value(&result)
+}
+
+#[wasm_bindgen]
+impl MerkleConfig {
+    /// Constructor for `MerkleConfig`. Without a depth the tree is as deep as the leaves need,
+    /// and without a hasher nodes are hashed with `MerkleHashAlgorithm.Poseidon2`.
+    #[wasm_bindgen(constructor)]
+    pub fn js_new(padding: MerklePadding, depth: Option<u32>, hasher: Option<MerkleHashAlgorithm>) -> Result<MerkleConfig, JsValue> {
+        let config = match depth {
+            Some(depth) => MerkleConfig::with_depth(depth, padding)?,
+            None => MerkleConfig::new(padding),
+        };
+        Ok(match hasher {
+            Some(hasher) => config.with_hasher(hasher),
+            None => config,
+        })
+    }
+
+    #[wasm_bindgen(getter, js_name = depth)]
+    pub fn js_depth(&self) -> Option<u32> {
+        self.depth
+    }
+
+    #[wasm_bindgen(getter, js_name = padding)]
+    pub fn js_padding(&self) -> MerklePadding {
+        self.padding
+    }
+
+    #[wasm_bindgen(getter, js_name = hasher)]
+    pub fn js_hasher(&self) -> MerkleHashAlgorithm {
+        self.hasher
     }
 }
 
+/// Hashes any number of credential values into merkle leaves, without padding.
 #[wasm_bindgen]
-pub fn sign_merkle_root(private_key: String, root: String, network: Network) -> Result<String, String> {
+pub fn hash_values_to_fields(values: JsValue, mode: ParseMode, network: Network) -> Result<Vec<String>, JsValue> {
+    let values: Vec<JsonValue> = from_js_value(values, "values")?;
+    let fields = match network {
+        Network::Testnet => fields_to_strings(&hash_values::<TestnetV0>(&values, mode)?),
+        Network::Mainnet => fields_to_strings(&hash_values::<MainnetV0>(&values, mode)?),
+    };
+    Ok(fields)
+}
+
+/// Returns the root of a merkle credential over any number of values, committing to the leaf
+/// count, the depth and the hasher, as `{ root, hasher, leaf_count, depth }`.
+#[wasm_bindgen]
+pub fn get_credential_merkle_root(values: JsValue, mode: ParseMode, config: &MerkleConfig, network: Network) -> Result<JsValue, JsValue> {
+    let values: Vec<JsonValue> = from_js_value(values, "values")?;
     match network {
-        Network::Testnet => sign_root::<TestnetV0>(&private_key, &root)
-            .map(|signature| signature.to_string())
-            .map_err(|e| e.to_string()),
-        Network::Mainnet => sign_root::<MainnetV0>(&private_key, &root)
-            .map(|signature| signature.to_string())
-            .map_err(|e| e.to_string()),
+        Network::Testnet => to_js_value(&CredentialTree::<TestnetV0>::from_values(&values, mode, config)?.merkle_root()),
+        Network::Mainnet => to_js_value(&CredentialTree::<MainnetV0>::from_values(&values, mode, config)?.merkle_root()),
     }
 }
 
+/// Returns the proof of the value at `index` in a merkle credential, as
+/// `{ index, leaf_count, hasher, siblings, directions }`.
 #[wasm_bindgen]
-pub fn get_merkle_proof(inputs: Vec<String>, index: usize, network: Network) -> Result<Vec<String>, String> {
+pub fn get_credential_merkle_proof(values: JsValue, index: usize, mode: ParseMode, config: &MerkleConfig, network: Network) -> Result<JsValue, JsValue> {
+    let values: Vec<JsonValue> = from_js_value(values, "values")?;
     match network {
-        Network::Testnet => {
-            let fields = hash_inputs_size_8::<TestnetV0>(inputs.iter().map(|s| s.as_str()).collect()).unwrap();
-            let tree = MerkleTree::<TestnetV0>::new(fields).unwrap();
-            let proof = tree.get_proof(index).unwrap();
-            Ok(proof.into_iter().map(|p| p.to_string()).collect())
-        }
-        Network::Mainnet => {
-            let fields = hash_inputs_size_8::<MainnetV0>(inputs.iter().map(|s| s.as_str()).collect()).unwrap();
-            let tree = MerkleTree::<MainnetV0>::new(fields).unwrap();
-            let proof = tree.get_proof(index).unwrap();
-            Ok(proof.into_iter().map(|p| p.to_string()).collect())
-        }
+        Network::Testnet => to_js_value(&CredentialTree::<TestnetV0>::from_values(&values, mode, config)?.get_proof(index)?),
+        Network::Mainnet => to_js_value(&CredentialTree::<MainnetV0>::from_values(&values, mode, config)?.get_proof(index)?),
     }
 }
 
+/// Checks a merkle credential proof of the leaf hash `leaf` against `root`.
 #[wasm_bindgen]
-pub fn get_merkle_root(inputs: Vec<String>, network: Network) -> Result<String, String> {
+pub fn verify_credential_merkle_proof(root: &str, leaf: &str, proof: JsValue, network: Network) -> Result<bool, JsValue> {
     match network {
-        Network::Testnet => {
-            let fields = hash_inputs_size_8::<TestnetV0>(inputs.iter().map(|s| s.as_str()).collect()).unwrap();
-            let tree = MerkleTree::<TestnetV0>::new(fields).unwrap();
-            Ok(tree.root().to_string())
-        }
-        Network::Mainnet => {
-            let fields = hash_inputs_size_8::<MainnetV0>(inputs.iter().map(|s| s.as_str()).collect()).unwrap();
-            let tree = MerkleTree::<MainnetV0>::new(fields).unwrap();
-            Ok(tree.root().to_string())
-        }
+        Network::Testnet => Ok(verify_credential_merkle_proof_impl::<TestnetV0>(root, leaf, proof)?),
+        Network::Mainnet => Ok(verify_credential_merkle_proof_impl::<MainnetV0>(root, leaf, proof)?),
     }
 }
 
+fn verify_credential_merkle_proof_impl<N: NetworkNative>(root: &str, leaf: &str, proof: JsValue) -> Result<bool, JsValue> {
+    let proof: MerkleProof<N> = from_js_value(proof, "proof")?;
+    let root = Field::<N>::from_str(root).map_err(|e| CustomError::parse("root", e))?;
+    let leaf = Field::<N>::from_str(leaf).map_err(|e| CustomError::parse("leaf", e))?;
+    Ok(proof.verify(&root, &leaf)?)
+}
+
+/// Returns one proof of the values at all the `indices` of a merkle credential, as
+/// `{ indices, leaf_count, depth, hasher, siblings }`, sharing the nodes the paths have in common.
 #[wasm_bindgen]
-pub fn get_merkle_tree(inputs: Vec<String>, network: Network) -> Result<JsValue, String> {
+pub fn get_credential_merkle_multiproof(values: JsValue, indices: JsValue, mode: ParseMode, config: &MerkleConfig, network: Network) -> Result<JsValue, JsValue> {
+    let values: Vec<JsonValue> = from_js_value(values, "values")?;
+    let indices: Vec<usize> = from_js_value(indices, "indices")?;
     match network {
-        Network::Testnet => {
-            let fields = hash_inputs_size_8::<TestnetV0>(inputs.iter().map(|s| s.as_str()).collect()).unwrap();
-            let tree = MerkleTree::<TestnetV0>::new(fields).unwrap();
-            let result: Vec<Vec<String>> = tree.levels()
-                .iter()
-                .map(|level| level.iter().map(|f| f.to_string()).collect())
-                .collect();
-            serde_wasm_bindgen::to_value(&result).map_err(|e| e.to_string())
-        }
-        Network::Mainnet => {
-            let fields = hash_inputs_size_8::<MainnetV0>(inputs.iter().map(|s| s.as_str()).collect()).unwrap();
-            let tree = MerkleTree::<MainnetV0>::new(fields).unwrap();
-            let result: Vec<Vec<String>> = tree.levels()
-                .iter()
-                .map(|level| level.iter().map(|f| f.to_string()).collect())
-                .collect();
-            serde_wasm_bindgen::to_value(&result).map_err(|e| e.to_string())
-        }
+        Network::Testnet => to_js_value(&CredentialTree::<TestnetV0>::from_values(&values, mode, config)?.get_multiproof(&indices)?),
+        Network::Mainnet => to_js_value(&CredentialTree::<MainnetV0>::from_values(&values, mode, config)?.get_multiproof(&indices)?),
     }
 }
 
+/// Checks a merkle credential multiproof of the leaf hashes `leaves`, given in the order of the
+/// proof's indices, against `root`.
+#[wasm_bindgen]
+pub fn verify_credential_merkle_multiproof(root: &str, leaves: JsValue, proof: JsValue, network: Network) -> Result<bool, JsValue> {
+    match network {
+        Network::Testnet => Ok(verify_credential_merkle_multiproof_impl::<TestnetV0>(root, leaves, proof)?),
+        Network::Mainnet => Ok(verify_credential_merkle_multiproof_impl::<MainnetV0>(root, leaves, proof)?),
+    }
+}
+
+fn verify_credential_merkle_multiproof_impl<N: NetworkNative>(root: &str, leaves: JsValue, proof: JsValue) -> Result<bool, JsValue> {
+    let proof: MerkleMultiproof<N> = from_js_value(proof, "proof")?;
+    let leaves: Vec<String> = from_js_value(leaves, "leaves")?;
+    let leaves = leaves.iter().enumerate()
+        .map(|(i, leaf)| Field::<N>::from_str(leaf).map_err(|e| CustomError::parse(format!("leaves[{}]", i), e)))
+        .collect::<Result<Vec<_>, _>>()?;
+    let root = Field::<N>::from_str(root).map_err(|e| CustomError::parse("root", e))?;
+    Ok(proof.verify(&root, &leaves)?)
+}
+
+/// Builds a merkle credential whose leaves bind each attribute name to its value and a fresh
+/// salt, returning `{ root, config, attributes }`. The whole credential must be handed to the
+/// holder, who needs it to make proofs.
+#[wasm_bindgen]
+pub fn build_attribute_tree(attributes: JsValue, mode: ParseMode, config: &MerkleConfig, network: Network) -> Result<JsValue, JsValue> {
+    let attributes: JsonValue = from_js_value(attributes, "attributes")?;
+    match network {
+        Network::Testnet => to_js_value(&AttributeTree::<TestnetV0>::new(&attributes, mode, config)?.credential()),
+        Network::Mainnet => to_js_value(&AttributeTree::<MainnetV0>::new(&attributes, mode, config)?.credential()),
+    }
+}
+
+/// Returns the proof of the attribute called `name`, from the credential returned by
+/// `build_attribute_tree`, as `{ attribute, proof }`.
+#[wasm_bindgen]
+pub fn get_attribute_proof(credential: JsValue, name: &str, network: Network) -> Result<JsValue, JsValue> {
+    match network {
+        Network::Testnet => get_attribute_proof_impl::<TestnetV0>(credential, name),
+        Network::Mainnet => get_attribute_proof_impl::<MainnetV0>(credential, name),
+    }
+}
+
+fn get_attribute_proof_impl<N: NetworkNative>(credential: JsValue, name: &str) -> Result<JsValue, JsValue> {
+    let credential: AttributeCredential<N> = from_js_value(credential, "credential")?;
+    to_js_value(&AttributeTree::from_credential(credential)?.get_proof(name)?)
+}
+
+/// Checks that a disclosed attribute is in the merkle credential with the given `root`.
+#[wasm_bindgen]
+pub fn verify_attribute_proof(root: &str, proof: JsValue, network: Network) -> Result<bool, JsValue> {
+    match network {
+        Network::Testnet => Ok(verify_attribute_proof_impl::<TestnetV0>(root, proof)?),
+        Network::Mainnet => Ok(verify_attribute_proof_impl::<MainnetV0>(root, proof)?),
+    }
+}
+
+fn verify_attribute_proof_impl<N: NetworkNative>(root: &str, proof: JsValue) -> Result<bool, JsValue> {
+    let proof: AttributeProof<N> = from_js_value(proof, "proof")?;
+    let root = Field::<N>::from_str(root).map_err(|e| CustomError::parse("root", e))?;
+    Ok(proof.verify(&root)?)
+}
+
+/// Returns one proof of all the attributes called `names`, from the credential returned by
+/// `build_attribute_tree`, as `{ attributes, proof }`.
+#[wasm_bindgen]
+pub fn get_attribute_multiproof(credential: JsValue, names: JsValue, network: Network) -> Result<JsValue, JsValue> {
+    match network {
+        Network::Testnet => get_attribute_multiproof_impl::<TestnetV0>(credential, names),
+        Network::Mainnet => get_attribute_multiproof_impl::<MainnetV0>(credential, names),
+    }
+}
+
+fn get_attribute_multiproof_impl<N: NetworkNative>(credential: JsValue, names: JsValue) -> Result<JsValue, JsValue> {
+    let credential: AttributeCredential<N> = from_js_value(credential, "credential")?;
+    let names: Vec<String> = from_js_value(names, "names")?;
+    to_js_value(&AttributeTree::from_credential(credential)?.get_multiproof(&names)?)
+}
+
+/// Checks that all the attributes disclosed by a multiproof are in the merkle credential with
+/// the given `root`.
+#[wasm_bindgen]
+pub fn verify_attribute_multiproof(root: &str, proof: JsValue, network: Network) -> Result<bool, JsValue> {
+    match network {
+        Network::Testnet => Ok(verify_attribute_multiproof_impl::<TestnetV0>(root, proof)?),
+        Network::Mainnet => Ok(verify_attribute_multiproof_impl::<MainnetV0>(root, proof)?),
+    }
+}
+
+fn verify_attribute_multiproof_impl<N: NetworkNative>(root: &str, proof: JsValue) -> Result<bool, JsValue> {
+    let proof: AttributeMultiproof<N> = from_js_value(proof, "proof")?;
+    let root = Field::<N>::from_str(root).map_err(|e| CustomError::parse("root", e))?;
+    Ok(proof.verify(&root)?)
+}
+
+/// Serializes a response into a plain JavaScript value.
+fn to_js_value<T: Serialize + ?Sized>(value: &T) -> Result<JsValue, JsValue> {
+    serde_wasm_bindgen::to_value(value)
+        .map_err(|e| CustomError::from(anyhow!("Failed to serialize response: {}", e)).into())
+}
+
+/// Deserializes a JavaScript value passed as `path`, reporting failures as a `PARSE_ERROR`.
+fn from_js_value<T: serde::de::DeserializeOwned>(value: JsValue, path: &str) -> Result<T, JsValue> {
+    serde_wasm_bindgen::from_value(value)
+        .map_err(|e| CustomError::parse(path, e).into())
+}
+
 /// Exposes a Rust function to JavaScript for signing messages.
 /// Returns the response as `SignResponse` or a `JsValue` error.
 #[wasm_bindgen]
@@ -104,13 +300,279 @@ pub fn sign_message(
         Network::Mainnet => sign_message_with_logger::<MainnetV0>(private_key, message, hash_alg, &ConsoleLogger),
     };
 
-    result
-        .map(|(signature, hash)| SignResponse::new(signature, hash))
-        .map_err(|err| JsValue::from_str(&err.to_string()))
+    let (signature, hash) = result?;
+    Ok(SignResponse::new(signature, hash))
+}
+
+/// Encrypts a private key under `passphrase` and returns the keystore JSON envelope.
+#[wasm_bindgen]
+pub fn encrypt_private_key(mut private_key: String, passphrase: &str, network: Network) -> Result<String, JsValue> {
+    let keystore = match network {
+        Network::Testnet => keystore::encrypt_private_key::<TestnetV0>(&private_key, passphrase),
+        Network::Mainnet => keystore::encrypt_private_key::<MainnetV0>(&private_key, passphrase),
+    };
+    private_key.zeroize();
+    Ok(keystore?.to_json()?)
+}
+
+/// Signs a message like `sign_message` with the private key of a keystore JSON envelope.
+#[wasm_bindgen]
+pub fn sign_message_with_keystore(
+    keystore: &str,
+    passphrase: &str,
+    message: SignInboundMessage,
+    hash_alg: HashAlgorithm,
+    network: Network
+) -> Result<SignResponse, JsValue> {
+    let issuer = Issuer::from_keystore(&Keystore::from_json(keystore)?, passphrase, network)?;
+    issuer.js_sign_credential(&message, hash_alg)
+}
+
+/// Signs a merkle root like `sign_merkle_root` with the private key of a keystore JSON envelope.
+#[wasm_bindgen]
+pub fn sign_merkle_root_with_keystore(keystore: &str, passphrase: &str, root: String, network: Network) -> Result<String, JsValue> {
+    let issuer = Issuer::from_keystore(&Keystore::from_json(keystore)?, passphrase, network)?;
+    Ok(issuer.sign_merkle_root(&root)?)
+}
+
+/// Signs many messages with one private key, parsing the key once.
+/// `messages` is an array of `{ data, schema?, mode? }`. Returns one entry per message,
+/// `{ outcome: "ok", signature, hash }` or `{ outcome: "error", code, message, path }`.
+#[wasm_bindgen]
+pub fn sign_messages_batch(
+    private_key: String,
+    messages: JsValue,
+    hash_alg: HashAlgorithm,
+    network: Network
+) -> Result<JsValue, JsValue> {
+    let messages: Vec<SignInboundMessage> = from_js_value(messages, "messages")?;
+    let results = match network {
+        Network::Testnet => batch::sign_messages_batch::<TestnetV0>(&private_key, &messages, hash_alg)?,
+        Network::Mainnet => batch::sign_messages_batch::<MainnetV0>(&private_key, &messages, hash_alg)?,
+    };
+    let results: Vec<BatchResult<SignResponse>> = results.into_iter()
+        .map(|result| result.map(|(signature, hash)| SignResponse::new(signature, hash)).into())
+        .collect();
+    to_js_value(&results)
+}
+
+/// Signs the credential with a freshly generated salt appended as its last member.
+/// Returns the signature, hash, salt and `PublicCredentials` literal as `IssuedCredential`.
+#[wasm_bindgen]
+pub fn issue_credential(
+    private_key: String,
+    message: SignInboundMessage,
+    hash_alg: HashAlgorithm,
+    network: Network
+) -> Result<IssuedCredential, JsValue> {
+    let result = match network {
+        Network::Testnet => issuance::issue_credential::<TestnetV0>(private_key, message, hash_alg, SaltSource::Random, &ConsoleLogger),
+        Network::Mainnet => issuance::issue_credential::<MainnetV0>(private_key, message, hash_alg, SaltSource::Random, &ConsoleLogger),
+    };
+
+    Ok(result?)
+}
+
+/// Signs the credential with a salt derived from `secret`, the credential subject and `context`.
+#[wasm_bindgen]
+pub fn issue_credential_with_derived_salt(
+    private_key: String,
+    message: SignInboundMessage,
+    hash_alg: HashAlgorithm,
+    secret: &[u8],
+    context: &str,
+    network: Network
+) -> Result<IssuedCredential, JsValue> {
+    let salt = SaltSource::Derived { secret, context };
+    let result = match network {
+        Network::Testnet => issuance::issue_credential::<TestnetV0>(private_key, message, hash_alg, salt, &ConsoleLogger),
+        Network::Mainnet => issuance::issue_credential::<MainnetV0>(private_key, message, hash_alg, salt, &ConsoleLogger),
+    };
+
+    Ok(result?)
+}
+
+/// Signs the credential with a derived salt like `issue_credential_with_derived_salt`, but fails
+/// with `ALREADY_ISSUED` when its `issued` key is among `issued`, a snapshot of the mapping keys.
+#[wasm_bindgen]
+pub fn issue_credential_with_derived_salt_checked(
+    private_key: String,
+    message: SignInboundMessage,
+    hash_alg: HashAlgorithm,
+    secret: &[u8],
+    context: &str,
+    issued: Vec<String>,
+    network: Network
+) -> Result<IssuedCredential, JsValue> {
+    let salt = SaltSource::Derived { secret, context };
+    let result = match network {
+        Network::Testnet => issuance::issue_credential_checked::<TestnetV0>(private_key, message, hash_alg, salt, &issued, &ConsoleLogger),
+        Network::Mainnet => issuance::issue_credential_checked::<MainnetV0>(private_key, message, hash_alg, salt, &issued, &ConsoleLogger),
+    };
+
+    Ok(result?)
+}
+
+/// Computes the `issued` mapping key `BHP256::commit_to_group(subject, salt)`.
+#[wasm_bindgen]
+pub fn issuance_commitment(subject: &str, salt: &str, network: Network) -> Result<String, JsValue> {
+    let commitment = match network {
+        Network::Testnet => issuance_commitment_impl::<TestnetV0>(subject, salt)?,
+        Network::Mainnet => issuance_commitment_impl::<MainnetV0>(subject, salt)?,
+    };
+    Ok(commitment)
+}
+
+fn issuance_commitment_impl<N: NetworkNative>(subject: &str, salt: &str) -> Result<String, CustomError> {
+    let subject = Address::<N>::from_str(subject).map_err(|e| CustomError::parse("subject", e))?;
+    let salt = Scalar::<N>::from_str(salt).map_err(|e| CustomError::parse("salt", e))?;
+    Ok(commitments::issuance_commitment(&subject, &salt)?.to_string())
+}
+
+/// Returns a fresh random scalar salt.
+#[wasm_bindgen]
+pub fn generate_salt(network: Network) -> String {
+    match network {
+        Network::Testnet => generate_salt_native::<TestnetV0, _>(&mut secure_rng()).to_string(),
+        Network::Mainnet => generate_salt_native::<MainnetV0, _>(&mut secure_rng()).to_string(),
+    }
+}
+
+/// Derives a scalar salt from an issuer secret, a subject address and a context string.
+#[wasm_bindgen]
+pub fn derive_salt(secret: &[u8], subject: &str, context: &str, network: Network) -> Result<String, JsValue> {
+    let salt = match network {
+        Network::Testnet => derive_salt_native::<TestnetV0>(secret, subject, context)?.to_string(),
+        Network::Mainnet => derive_salt_native::<MainnetV0>(secret, subject, context)?.to_string(),
+    };
+    Ok(salt)
+}
+
+#[wasm_bindgen]
+impl IssuedCredential {
+    /// Returns the signature over the salted credential.
+    #[wasm_bindgen(getter, js_name = signature)]
+    pub fn js_signature(&self) -> String {
+        self.signature.clone()
+    }
+
+    /// Returns the hash of the salted credential.
+    #[wasm_bindgen(getter, js_name = hash)]
+    pub fn js_hash(&self) -> String {
+        self.hash.clone()
+    }
+
+    /// Returns the salt as a scalar literal.
+    #[wasm_bindgen(getter, js_name = salt)]
+    pub fn js_salt(&self) -> String {
+        self.salt.clone()
+    }
+
+    /// Returns the `PublicCredentials { salt }` struct literal.
+    #[wasm_bindgen(getter, js_name = public_credentials)]
+    pub fn js_public_credentials(&self) -> String {
+        self.public_credentials.clone()
+    }
+
+    /// Returns the `issued` mapping key the credential will be stored under.
+    #[wasm_bindgen(getter, js_name = issued_key)]
+    pub fn js_issued_key(&self) -> Option<String> {
+        self.issued_key.clone()
+    }
+}
+
+#[wasm_bindgen]
+impl Issuer {
+    /// Parses the private key once. The string passed in is zeroized after parsing.
+    #[wasm_bindgen(constructor)]
+    pub fn js_new(mut private_key: String, network: Network) -> Result<Issuer, JsValue> {
+        let issuer = Issuer::new(&private_key, network);
+        private_key.zeroize();
+        Ok(issuer?)
+    }
+
+    /// Unlocks an issuer from a keystore JSON envelope created by `encrypt_private_key`.
+    #[wasm_bindgen(js_name = from_keystore)]
+    pub fn js_from_keystore(keystore: &str, passphrase: &str, network: Network) -> Result<Issuer, JsValue> {
+        Ok(Issuer::from_keystore(&Keystore::from_json(keystore)?, passphrase, network)?)
+    }
+
+    /// Encrypts the issuer key under `passphrase` and returns the keystore JSON envelope.
+    #[wasm_bindgen(js_name = to_keystore)]
+    pub fn js_to_keystore(&self, passphrase: &str) -> Result<String, JsValue> {
+        Ok(self.to_keystore(passphrase)?.to_json()?)
+    }
+
+    /// Returns the issuer address.
+    #[wasm_bindgen(js_name = address)]
+    pub fn js_address(&self) -> String {
+        self.address().to_string()
+    }
+
+    /// Returns the view key of the issuer account.
+    #[wasm_bindgen(js_name = view_key)]
+    pub fn js_view_key(&self) -> Result<String, JsValue> {
+        Ok(self.view_key()?)
+    }
+
+    /// Signs a credential like `sign_message` without passing the key again.
+    #[wasm_bindgen(js_name = sign_credential)]
+    pub fn js_sign_credential(&self, message: &SignInboundMessage, hash_alg: HashAlgorithm) -> Result<SignResponse, JsValue> {
+        let (signature, hash) = self.sign_credential(message, hash_alg, &ConsoleLogger)?;
+        Ok(SignResponse::new(signature, hash))
+    }
+
+    /// Signs a merkle root like `sign_merkle_root` without passing the key again.
+    #[wasm_bindgen(js_name = sign_merkle_root)]
+    pub fn js_sign_merkle_root(&self, root: &str) -> Result<String, JsValue> {
+        Ok(self.sign_merkle_root(root)?)
+    }
+
+    /// Hashes up to seven credential values behind the issuer leaf and signs the merkle root.
+    /// `values` is an array of Aleo literal strings, booleans or `{ type, value }` objects.
+    #[wasm_bindgen(js_name = build_merkle_credential)]
+    pub fn js_build_merkle_credential(&self, values: JsValue, mode: ParseMode) -> Result<MerkleCredential, JsValue> {
+        let values: Vec<JsonValue> = from_js_value(values, "values")?;
+        Ok(self.build_merkle_credential(&values, mode)?)
+    }
+}
+
+#[wasm_bindgen]
+impl MerkleCredential {
+    /// Returns the issuer signature over the root.
+    #[wasm_bindgen(getter, js_name = signature)]
+    pub fn js_signature(&self) -> String {
+        self.signature.clone()
+    }
+
+    /// Returns the merkle root as a field literal.
+    #[wasm_bindgen(getter, js_name = root)]
+    pub fn js_root(&self) -> String {
+        self.root.clone()
+    }
+
+    /// Returns the eight leaf hashes, the `leaves_hashes` input of `issue`.
+    #[wasm_bindgen(getter, js_name = leaves)]
+    pub fn js_leaves(&self) -> Vec<String> {
+        self.leaves.clone()
+    }
+
+    /// Returns the `merkle_proof` input of `verify` for the leaf at `index`.
+    #[wasm_bindgen(js_name = proof)]
+    pub fn js_proof(&self, index: usize) -> Result<Vec<String>, JsValue> {
+        Ok(self.proof(index)?.to_vec())
+    }
+
+    /// Returns the credential as a plain JavaScript object.
+    #[wasm_bindgen(js_name = toJSON)]
+    pub fn to_js(&self) -> Result<JsValue, JsValue> {
+        to_js_value(self)
+    }
 }
 
 /// A struct representing the response of a signing operation.
 #[wasm_bindgen]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct SignResponse {
     pub(crate) signature: String,
     pub(crate) hash: String,
@@ -165,6 +627,37 @@ pub enum HashAlgorithm {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SignInboundMessage {
     pub(crate) data: JsonValue,
+    pub(crate) schema: Option<CredentialSchema>,
+    #[serde(default)]
+    pub(crate) mode: ParseMode,
+    /// Opt-in domain separation, see [`SigningDomain`].
+    #[serde(default)]
+    pub(crate) domain: Option<SigningDomain>,
+}
+
+impl SignInboundMessage {
+    /// Creates a message from JSON data, parsed in [`ParseMode::Lenient`] without a schema or domain.
+    pub fn from_json(data: JsonValue) -> Self {
+        Self { data, schema: None, mode: ParseMode::Lenient, domain: None }
+    }
+
+    /// Parses untyped string values in `mode` instead.
+    pub fn with_mode(mut self, mode: ParseMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Validates and encodes the data against `schema` when signed.
+    pub fn with_credential_schema(mut self, schema: CredentialSchema) -> Self {
+        self.schema = Some(schema);
+        self
+    }
+
+    /// Binds the signature to `domain`, see [`SigningDomain`].
+    pub fn with_domain(mut self, domain: Option<SigningDomain>) -> Self {
+        self.domain = domain;
+        self
+    }
 }
 
 #[wasm_bindgen]
@@ -172,44 +665,395 @@ impl SignInboundMessage {
     /// Constructor for `SignInboundMessage`.
     #[wasm_bindgen(constructor)]
     pub fn new(data: JsValue) -> Result<SignInboundMessage, JsValue> {
-         // Convert JsValue to serde_json::Value
-         let data: JsonValue = serde_wasm_bindgen::from_value(data)
-         .map_err(|e| JsValue::from_str(&format!("Failed to parse data: {}", e)))?;
+        // Convert JsValue to serde_json::Value
+        let data: JsonValue = from_js_value(data, "data")?;
 
         // Create a new instance with provided values
-        Ok(SignInboundMessage { data })
+        Ok(SignInboundMessage::from_json(data))
+    }
+
+    /// Creates a message that is validated and encoded against `schema` when signed.
+    pub fn with_schema(data: JsValue, schema: &CredentialSchema) -> Result<SignInboundMessage, JsValue> {
+        Ok(SignInboundMessage::new(data)?.with_credential_schema(schema.clone()))
+    }
+
+    /// Creates a message whose string values must all be Aleo literals with a type suffix.
+    pub fn strict(data: JsValue) -> Result<SignInboundMessage, JsValue> {
+        Ok(SignInboundMessage::new(data)?.with_mode(ParseMode::Strict))
+    }
+
+    /// Returns how untyped string values are parsed when no schema is attached.
+    #[wasm_bindgen(getter)]
+    pub fn mode(&self) -> ParseMode {
+        self.mode
+    }
+
+    /// Sets how untyped string values are parsed when no schema is attached.
+    #[wasm_bindgen(setter)]
+    pub fn set_mode(&mut self, mode: ParseMode) {
+        self.mode = mode;
+    }
+
+    /// Returns the domain the signature is bound to, if any.
+    #[wasm_bindgen(getter)]
+    pub fn domain(&self) -> Option<SigningDomain> {
+        self.domain.clone()
+    }
+
+    /// Binds the signature to a program, network and schema version, or removes the binding.
+    #[wasm_bindgen(setter)]
+    pub fn set_domain(&mut self, domain: Option<SigningDomain>) {
+        self.domain = domain;
     }
 
     #[wasm_bindgen(getter)]
     pub fn data(&self) -> Result<JsValue, JsValue> {
-        serde_wasm_bindgen::to_value(&self.data)
-            .map_err(|e| JsValue::from_str(&format!("Failed to serialize data: {}", e)))
+        to_js_value(&self.data)
+    }
+}
+
+#[wasm_bindgen]
+impl CredentialSchema {
+    /// Constructor for `CredentialSchema` from `{ name, members: [{ name, type, required?, constraints? }] }`.
+    #[wasm_bindgen(constructor)]
+    pub fn from_js(schema: JsValue) -> Result<CredentialSchema, JsValue> {
+        from_js_value(schema, "schema")
+    }
+
+    /// Returns the name of the Leo struct described by the schema.
+    #[wasm_bindgen(getter, js_name = name)]
+    pub fn js_name(&self) -> String {
+        self.name.clone()
+    }
+
+    /// Returns the schema as a plain JavaScript object.
+    #[wasm_bindgen(js_name = toJSON)]
+    pub fn to_js(&self) -> Result<JsValue, JsValue> {
+        to_js_value(self)
     }
 }
 
+#[wasm_bindgen]
+impl SigningDomain {
+    /// Constructor for `SigningDomain` from a program id such as `verify_poseidon2_zpass.aleo`,
+    /// a schema id and its version.
+    #[wasm_bindgen(constructor)]
+    pub fn js_new(program: &str, schema: &str, version: u32) -> SigningDomain {
+        SigningDomain::new(program, schema, version)
+    }
+
+    #[wasm_bindgen(getter, js_name = program)]
+    pub fn js_program(&self) -> String {
+        self.program.clone()
+    }
+
+    #[wasm_bindgen(getter, js_name = schema)]
+    pub fn js_schema(&self) -> String {
+        self.schema.clone()
+    }
+
+    #[wasm_bindgen(getter, js_name = version)]
+    pub fn js_version(&self) -> u32 {
+        self.version
+    }
+
+    /// Returns the domain tag as a field literal for the Leo program to compare against.
+    #[wasm_bindgen(js_name = tag)]
+    pub fn js_tag(&self, network: Network) -> Result<String, JsValue> {
+        let tag = match network {
+            Network::Testnet => self.tag::<TestnetV0>()?.to_string(),
+            Network::Mainnet => self.tag::<MainnetV0>()?.to_string(),
+        };
+        Ok(tag)
+    }
+
+    /// Returns the `DomainTag` struct literal the tag is hashed from.
+    #[wasm_bindgen(js_name = tag_struct)]
+    pub fn js_tag_struct(&self, network: Network) -> Result<String, JsValue> {
+        let tag_struct = match network {
+            Network::Testnet => self.tag_struct::<TestnetV0>()?.to_string(),
+            Network::Mainnet => self.tag_struct::<MainnetV0>()?.to_string(),
+        };
+        Ok(tag_struct)
+    }
+}
+
+/// Computes the `invalidated` mapping key of a credential for `layout`, see [`commitments::invalidation_commitment`].
+/// `credentials` holds the plain `FullCredentials` members including `salt`.
+#[wasm_bindgen]
+pub fn invalidation_commitment(credentials: JsValue, layout: InvalidationLayout, network: Network) -> Result<String, JsValue> {
+    let credentials: JsonValue = from_js_value(credentials, "credentials")?;
+    let commitment = match network {
+        Network::Testnet => commitments::invalidation_commitment::<TestnetV0>(&credentials, layout)?.to_string(),
+        Network::Mainnet => commitments::invalidation_commitment::<MainnetV0>(&credentials, layout)?.to_string(),
+    };
+    Ok(commitment)
+}
+
+/// Computes `BHP256::commit_to_group(data, data.salt)` for data encoded against `schema`,
+/// e.g. the members of a hiding `ZPass` record with the `InvalidateZPass` schema.
+#[wasm_bindgen]
+pub fn commit_credentials(schema: &CredentialSchema, data: JsValue, network: Network) -> Result<String, JsValue> {
+    let data: JsonValue = from_js_value(data, "data")?;
+    let commitment = match network {
+        Network::Testnet => commitments::commit_credentials::<TestnetV0>(schema, &data)?.to_string(),
+        Network::Mainnet => commitments::commit_credentials::<MainnetV0>(schema, &data)?.to_string(),
+    };
+    Ok(commitment)
+}
+
+/// Computes the members of the hiding `ZPass` record `program` creates for the plain
+/// `FullCredentials` (including `salt`), rendered as an Aleo struct literal.
+#[wasm_bindgen]
+pub fn hide_credentials(program: ZPassProgram, credentials: JsValue, network: Network) -> Result<String, JsValue> {
+    let credentials: JsonValue = from_js_value(credentials, "credentials")?;
+    let members = match network {
+        Network::Testnet => struct_literal(&hiding::hide_credentials::<TestnetV0>(program, &credentials)?),
+        Network::Mainnet => struct_literal(&hiding::hide_credentials::<MainnetV0>(program, &credentials)?),
+    };
+    Ok(members)
+}
+
+/// Checks plain attribute openings such as `{ dob: "20000101u32" }` against a decrypted hiding
+/// `ZPass` record. Fails with `CONSTRAINT_VIOLATION` on the first attribute that does not open.
+#[wasm_bindgen]
+pub fn check_hiding_openings(program: ZPassProgram, record: &str, openings: JsValue, network: Network) -> Result<(), JsValue> {
+    let openings: JsonValue = from_js_value(openings, "openings")?;
+    match network {
+        Network::Testnet => hiding::check_openings::<TestnetV0>(program, record, &openings)?,
+        Network::Mainnet => hiding::check_openings::<MainnetV0>(program, record, &openings)?,
+    };
+    Ok(())
+}
+
+/// Builds the inputs of `more_than_18(dob, dob_check, zpass)` from a decrypted hiding record.
+/// Returns `{ program, function, inputs: [{ name, value, visibility }] }`.
+#[wasm_bindgen]
+pub fn more_than_18_inputs(program: ZPassProgram, record: &str, dob: JsValue, dob_check: JsValue, network: Network) -> Result<JsValue, JsValue> {
+    let dob: JsonValue = from_js_value(dob, "dob")?;
+    let dob_check: JsonValue = from_js_value(dob_check, "dob_check")?;
+    let result = match network {
+        Network::Testnet => hiding::more_than_18_inputs::<TestnetV0>(program, record, &dob, &dob_check),
+        Network::Mainnet => hiding::more_than_18_inputs::<MainnetV0>(program, record, &dob, &dob_check),
+    };
+    to_js_value(&result?)
+}
+
+/// Decrypts a `record1...` ciphertext, or checks the owner of a plaintext record, with a view key.
+/// Returns the plaintext record with visibility suffixes.
+#[wasm_bindgen]
+pub fn decrypt_record(record: &str, view_key: &str, network: Network) -> Result<String, JsValue> {
+    let plaintext = match network {
+        Network::Testnet => records::decrypt_record(record, &records::parse_view_key::<TestnetV0>(view_key)?)?.to_string(),
+        Network::Mainnet => records::decrypt_record(record, &records::parse_view_key::<MainnetV0>(view_key)?)?.to_string(),
+    };
+    Ok(plaintext)
+}
+
+/// Decrypts a record with a view key and parses it as the `ZPass` of `program`.
+/// Returns `{ program, nonce, zpass: { layout, owner, ... } }` with every member as a literal string.
+#[wasm_bindgen]
+pub fn decrypt_zpass_record(program: ZPassProgram, record: &str, view_key: &str, network: Network) -> Result<JsValue, JsValue> {
+    match network {
+        Network::Testnet => to_js_value(&ZPassRecord::decrypt(program, record, &records::parse_view_key::<TestnetV0>(view_key)?)?),
+        Network::Mainnet => to_js_value(&ZPassRecord::decrypt(program, record, &records::parse_view_key::<MainnetV0>(view_key)?)?),
+    }
+}
+
+/// Finds the records of an account in a JSON or NDJSON dump of blocks or transactions.
+///
+/// `key` is a view key, or a private key to also compute serial numbers. `filter` is
+/// `{ programs?: string[], record_name?: string }` and `checkpoint` the result of an earlier call
+/// to resume from, or `undefined`. Returns `{ height, records }`, the next checkpoint.
+#[wasm_bindgen]
+pub fn find_records(dump: &str, mut key: String, filter: JsValue, checkpoint: JsValue, network: Network) -> Result<JsValue, JsValue> {
+    let filter: Option<RecordFilter> = from_js_value(filter, "filter")?;
+    let checkpoint: Option<RecordScan> = from_js_value(checkpoint, "checkpoint")?;
+    let result = match network {
+        Network::Testnet => find_records_impl::<TestnetV0>(dump, &key, filter.unwrap_or_default(), checkpoint.unwrap_or_default()),
+        Network::Mainnet => find_records_impl::<MainnetV0>(dump, &key, filter.unwrap_or_default(), checkpoint.unwrap_or_default()),
+    };
+    key.zeroize();
+    to_js_value(&result?)
+}
+
+fn find_records_impl<N: NetworkNative>(dump: &str, key: &str, filter: RecordFilter, checkpoint: RecordScan) -> Result<RecordScan, CustomError> {
+    let finder = match key.starts_with("APrivateKey1") {
+        true => RecordFinder::from_private_key(parse_private_key::<N>(key)?, filter)?,
+        false => RecordFinder::new(records::parse_view_key::<N>(key)?, filter)?,
+    };
+    let mut finder = finder.resume(checkpoint);
+    finder.scan_dump(dump)?;
+    Ok(finder.into_scan())
+}
+
+/// Extracts the schema of the struct or record called `name` from a Leo source file.
+#[wasm_bindgen]
+pub fn schema_from_leo(source: &str, name: &str) -> Result<CredentialSchema, JsValue> {
+    Ok(CredentialSchema::from_leo(source, name)?)
+}
+
+/// Extracts the schema of the struct or record called `name` from compiled Aleo instructions.
+#[wasm_bindgen]
+pub fn schema_from_aleo(source: &str, name: &str, network: Network) -> Result<CredentialSchema, JsValue> {
+    let result = match network {
+        Network::Testnet => CredentialSchema::from_aleo::<TestnetV0>(source, name),
+        Network::Mainnet => CredentialSchema::from_aleo::<MainnetV0>(source, name),
+    };
+    Ok(result?)
+}
+
+/// Builds the ordered inputs of a ZPass transition from a signature and the credential data.
+/// Returns `{ program, function, inputs: [{ name, value, visibility }] }`.
+#[wasm_bindgen]
+pub fn build_transition_inputs(
+    transition: ZPassTransition,
+    signature: &str,
+    data: JsValue,
+    network: Network
+) -> Result<JsValue, JsValue> {
+    let data: JsonValue = from_js_value(data, "data")?;
+    let result = match network {
+        Network::Testnet => transition_inputs::build_transition_inputs::<TestnetV0>(transition, signature, &data),
+        Network::Mainnet => transition_inputs::build_transition_inputs::<MainnetV0>(transition, signature, &data),
+    };
+    to_js_value(&result?)
+}
+
+/// Encodes arbitrary UTF-8 text into exactly `length` fields: the byte length followed by
+/// 31-byte little-endian chunks, zero padded. Matches a Leo `[field; length]` member.
+#[wasm_bindgen]
+pub fn encode_string_to_fields(text: &str, length: u32, network: Network) -> Result<Vec<String>, JsValue> {
+    let fields = match network {
+        Network::Testnet => encode_string::<TestnetV0>(text, length).map(|fields| fields_to_strings(&fields)),
+        Network::Mainnet => encode_string::<MainnetV0>(text, length).map(|fields| fields_to_strings(&fields)),
+    };
+    Ok(fields?)
+}
+
+/// Hashes arbitrary UTF-8 text into a single field with Poseidon2 over its length-prefixed chunks.
+#[wasm_bindgen]
+pub fn hash_string_to_field(text: &str, network: Network) -> Result<String, JsValue> {
+    let field = match network {
+        Network::Testnet => hash_string::<TestnetV0>(text).map(|field| field.to_string()),
+        Network::Mainnet => hash_string::<MainnetV0>(text).map(|field| field.to_string()),
+    };
+    Ok(field?)
+}
+
+/// Decodes fields produced by `encode_string_to_fields` back into the original text.
+#[wasm_bindgen]
+pub fn decode_string_from_fields(fields: Vec<String>, network: Network) -> Result<String, JsValue> {
+    fn decode<N: NetworkNative>(fields: &[String]) -> Result<String, CustomError> {
+        let fields = fields.iter().enumerate()
+            .map(|(i, field)| Field::<N>::from_str(field).map_err(|e| CustomError::parse(format!("fields[{}]", i), e)))
+            .collect::<Result<Vec<_>, _>>()?;
+        decode_string::<N>(&fields)
+    }
+    let text = match network {
+        Network::Testnet => decode::<TestnetV0>(&fields),
+        Network::Mainnet => decode::<MainnetV0>(&fields),
+    };
+    Ok(text?)
+}
+
 /// Exposes a Rust function to JavaScript for converting a string option to a field value.
 #[wasm_bindgen]
 pub fn get_field_from_value(
     str: Option<String>, 
     network: Network
-) -> Result<String, String> {
+) -> Result<String, JsValue> {
     let field = match network {
-        Network::Testnet => string_to_field::<TestnetV0>(str).map_err(|e| e.to_string())?.to_string(),
-        Network::Mainnet => string_to_field::<MainnetV0>(str).map_err(|e| e.to_string())?.to_string(),
+        Network::Testnet => string_to_field::<TestnetV0>(str).map(|field| field.to_string()),
+        Network::Mainnet => string_to_field::<MainnetV0>(str).map(|field| field.to_string()),
     };
-    Ok(field)
+    field.map_err(|e| CustomError::parse("value", e).into())
 }
 
-macro_rules! verify_credential_impl {
-    ($signature:expr, $address:expr, $message:expr, $network:ty) => {{
-        let (_, signature_bytes) = Signature::<$network>::parse($signature).unwrap();
-        let (_, address_bytes) = Address::<$network>::parse($address).unwrap();
-        let message_bytes = string_to_value_fields::<$network>($message);
-        match verify_signature_with_address_and_message(&signature_bytes, &address_bytes, message_bytes.as_slice()) {
-            true => Ok(true),
-            false => Err("Signature verification failed".to_string())
-        }
-    }}
+fn verify_signed_credential_impl<N: NetworkNative>(signature: &str, address: &str, message: &str) -> Result<bool, CustomError> {
+    let signature = Signature::<N>::from_str(signature)
+        .map_err(|e| CustomError::parse("signature", e))?;
+    let address = Address::<N>::from_str(address)
+        .map_err(|e| CustomError::parse("address", e))?;
+    let message_fields = string_to_value_fields::<N>(message)?;
+    match verify_signature_with_address_and_message(&signature, &address, message_fields.as_slice()) {
+        true => Ok(true),
+        false => Err(CustomError::VerificationFailed("Signature verification failed".to_string())),
+    }
+}
+
+/// Verifies a credential end to end: rebuilds its hash from the data, then checks the
+/// signature against `issuer`. Returns the status together with the rebuilt hash and signer.
+#[wasm_bindgen]
+pub fn verify_credential(
+    signature: &str,
+    issuer: &str,
+    message: &SignInboundMessage,
+    hash_alg: HashAlgorithm,
+    expected_hash: Option<String>,
+    network: Network
+) -> Result<CredentialVerification, JsValue> {
+    let result = match network {
+        Network::Testnet => verification::verify_credential::<TestnetV0>(message, hash_alg, signature, issuer, expected_hash.as_deref()),
+        Network::Mainnet => verification::verify_credential::<MainnetV0>(message, hash_alg, signature, issuer, expected_hash.as_deref()),
+    };
+    Ok(result?)
+}
+
+/// Verifies a decrypted `verify_poseidon2_zpass.aleo/ZPass` record plaintext against the issuer
+/// signature offline, rebuilding `FullCredentials` with the record owner as `subject`.
+#[wasm_bindgen]
+pub fn verify_zpass_record(record: &str, signature: &str, network: Network) -> Result<CredentialVerification, JsValue> {
+    let result = match network {
+        Network::Testnet => verification::verify_zpass_record::<TestnetV0>(record, signature),
+        Network::Mainnet => verification::verify_zpass_record::<MainnetV0>(record, signature),
+    };
+    Ok(result?)
+}
+
+/// Verifies many credentials against one issuer.
+/// `credentials` is an array of `{ data, schema?, mode?, signature, hash? }`. Returns one entry
+/// per credential, `{ outcome: "ok", status, hash, signer }` or `{ outcome: "error", code, message, path }`.
+#[wasm_bindgen]
+pub fn verify_credentials_batch(
+    credentials: JsValue,
+    issuer: &str,
+    hash_alg: HashAlgorithm,
+    network: Network
+) -> Result<JsValue, JsValue> {
+    let credentials: Vec<BatchCredential> = from_js_value(credentials, "credentials")?;
+    let results: Vec<BatchResult<CredentialVerification>> = match network {
+        Network::Testnet => batch::verify_credentials_batch::<TestnetV0>(&credentials, hash_alg, issuer)?,
+        Network::Mainnet => batch::verify_credentials_batch::<MainnetV0>(&credentials, hash_alg, issuer)?,
+    }.into_iter().map(BatchResult::from).collect();
+    to_js_value(&results)
+}
+
+#[wasm_bindgen]
+impl CredentialVerification {
+    /// Returns the verification status.
+    #[wasm_bindgen(getter, js_name = status)]
+    pub fn js_status(&self) -> VerificationStatus {
+        self.status
+    }
+
+    /// Returns whether the credential verified.
+    #[wasm_bindgen(getter, js_name = valid)]
+    pub fn js_valid(&self) -> bool {
+        self.is_valid()
+    }
+
+    /// Returns the hash rebuilt from the credential data.
+    #[wasm_bindgen(getter, js_name = hash)]
+    pub fn js_hash(&self) -> String {
+        self.hash.clone()
+    }
+
+    /// Returns the address whose key produced the signature.
+    #[wasm_bindgen(getter, js_name = signer)]
+    pub fn js_signer(&self) -> String {
+        self.signer.clone()
+    }
 }
 
 #[wasm_bindgen]
@@ -218,9 +1062,10 @@ pub fn verify_signed_credential(
     address: &str, 
     message: &str,
     network: Network
-) -> Result<bool, String> {
-    match network {
-        Network::Testnet => verify_credential_impl!(signature, address, message, TestnetV0),
-        Network::Mainnet => verify_credential_impl!(signature, address, message, MainnetV0),
-    }
+) -> Result<bool, JsValue> {
+    let verified = match network {
+        Network::Testnet => verify_signed_credential_impl::<TestnetV0>(signature, address, message),
+        Network::Mainnet => verify_signed_credential_impl::<MainnetV0>(signature, address, message),
+    };
+    Ok(verified?)
 }